@@ -0,0 +1,1888 @@
+//! Client library for OCS01 contracts on the Octra network: wallet/interface types,
+//! transaction signing, and the view/call HTTP helpers used by the `ocs01-auto` binary.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::json;
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io::Write,
+    sync::{atomic::{AtomicUsize, Ordering}, mpsc, Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use base64::{engine::general_purpose, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use sha2::{Digest, Sha256};
+use reqwest::blocking::{Client, Response};
+use rand::{Rng, RngCore};
+use aes_gcm::{aead::Aead, Aes256Gcm, KeyInit, Nonce};
+use argon2::Argon2;
+use zeroize::Zeroizing;
+
+#[cfg(feature = "async")]
+pub mod nonblocking;
+
+// =============================
+// Struct Definitions
+// =============================
+/// Wallet file contents (`wallet.json`).
+#[derive(Deserialize)]
+pub struct Wallet {
+    /// Base64 private key, or the output of [`encrypt_private_key`] when `enc` is set.
+    /// Optional when the key comes from `OCS01_PRIVATE_KEY` or `priv_file`.
+    #[serde(rename = "priv")]
+    pub priv_: Option<String>,
+    /// Path to a file holding the key in the same format as `priv`.
+    pub priv_file: Option<String>,
+    /// BIP-39 mnemonic to derive the key from with [`key_from_mnemonic`], instead of `priv`.
+    pub mnemonic: Option<String>,
+    /// SLIP-0010 path for `mnemonic`; defaults to [`DEFAULT_DERIVATION_PATH`].
+    pub derivation_path: Option<String>,
+    #[serde(default)]
+    pub enc: bool,
+    /// May be left out of a file that only holds a key, e.g. for `whoami`.
+    #[serde(default)]
+    pub addr: String,
+    /// One endpoint or a list of endpoints tried in order.
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub rpc: Vec<String>,
+    /// Request timeout in seconds.
+    pub timeout: Option<u64>,
+    /// Connect timeout in seconds.
+    pub connect_timeout: Option<u64>,
+    /// Idle connections kept open per node for reuse; 0 turns pooling off.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Seconds an idle pooled connection is kept open.
+    pub pool_idle_timeout: Option<u64>,
+    /// Seconds between TCP keep-alive probes on open connections; 0 turns them off.
+    pub tcp_keepalive: Option<u64>,
+    /// Extra HTTP headers sent with every request, e.g. an API key for a gated node.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Block explorer link template for submitted transactions, e.g.
+    /// `https://explorer.example/tx/{hash}`.
+    pub explorer_url: Option<String>,
+    /// PEM client certificate for nodes that require mutual TLS; needs `tls_key`.
+    pub tls_cert: Option<String>,
+    /// PEM (PKCS#8) private key for `tls_cert`.
+    pub tls_key: Option<String>,
+    /// PEM root CA to trust in addition to the system roots, e.g. for a self-signed node.
+    pub tls_ca: Option<String>,
+    /// Chain ID or name the node must report (see [`get_network`]), to catch a wallet
+    /// pointed at the wrong network.
+    pub expected_network: Option<String>,
+    /// Decimal places of the chain's token; defaults to 6, i.e. [`MICRO_PER_OCT`].
+    pub decimals: Option<u32>,
+}
+
+impl Wallet {
+    /// The token precision configured by `decimals`.
+    pub fn denomination(&self) -> Result<Denomination> {
+        self.decimals.map_or(Ok(Denomination::default()), Denomination::from_decimals)
+    }
+
+    /// The explorer link for `tx_hash`, if `explorer_url` is configured.
+    pub fn explorer_link(&self, tx_hash: &str) -> Option<String> {
+        self.explorer_url.as_ref().map(|template| template.replace("{hash}", tx_hash))
+    }
+}
+
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(url) => vec![url],
+        OneOrMany::Many(urls) => urls,
+    })
+}
+
+/// A single method parameter as declared in the interface file.
+#[derive(Serialize, Deserialize)]
+pub struct Param {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub param_type: String,
+    pub example: Option<String>,
+    /// Lower bound for generated numbers (default 1).
+    pub min: Option<u64>,
+    /// Upper bound for generated numbers (default 100).
+    pub max: Option<u64>,
+    /// Length of generated strings (default 8); only valid on `string` params.
+    pub len: Option<usize>,
+}
+
+/// A contract method as declared in the interface file.
+#[derive(Serialize, Deserialize)]
+pub struct Method {
+    pub name: String,
+    pub label: String,
+    pub params: Vec<Param>,
+    #[serde(rename = "type")]
+    pub method_type: MethodType,
+    /// Gas (`ou`) for `call` methods; defaults to [`DEFAULT_OU`].
+    pub ou: Option<u64>,
+    /// Micro-OCT attached to a payable `call` method; defaults to 0.
+    pub amount: Option<u64>,
+    /// How many times to run the method in a row, with fresh params each time; defaults to 1.
+    pub repeat: Option<u32>,
+    /// Request timeout in seconds for this method, instead of the client-wide one.
+    pub timeout: Option<u64>,
+    /// Contract to call instead of [`Interface::contract`], e.g. when interfaces for
+    /// several contracts are combined into one run.
+    #[serde(default)]
+    pub contract: Option<String>,
+    /// Address sent as `caller` for a `view` method instead of the wallet's, to see what
+    /// the contract returns to someone else. Needs no key, since views are not signed.
+    #[serde(default)]
+    pub caller: Option<String>,
+}
+
+impl Method {
+    /// [`Method::timeout`] as a `Duration`.
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs)
+    }
+}
+
+/// Whether a method only reads state or submits a transaction; `"view"` or `"call"` in
+/// the interface file, matched exactly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MethodType {
+    View,
+    Call,
+}
+
+impl std::fmt::Display for MethodType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            MethodType::View => "view",
+            MethodType::Call => "call",
+        })
+    }
+}
+
+/// Interface file contents (`exec_interface.json`).
+#[derive(Serialize, Deserialize)]
+pub struct Interface {
+    pub contract: String,
+    #[serde(deserialize_with = "named_methods")]
+    pub methods: Vec<Method>,
+}
+
+/// Deserializes the methods one at a time so that an error, such as an unknown `type`,
+/// names the method it is in.
+fn named_methods<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Vec<Method>, D::Error> {
+    Vec::<serde_json::Value>::deserialize(deserializer)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let name = match value["name"].as_str() {
+                Some(name) => format!("'{}'", name),
+                None => format!("#{}", i + 1),
+            };
+            Method::deserialize(value).map_err(|e| serde::de::Error::custom(format!("method {}: {}", name, e)))
+        })
+        .collect()
+}
+
+/// HTTP client plus the node endpoints to use, in failover order.
+pub struct Rpc {
+    pub client: Client,
+    pub endpoints: Vec<String>,
+    /// Shared by every request made through this `Rpc`, including retries and failover.
+    pub limiter: Option<RateLimiter>,
+}
+
+impl Rpc {
+    pub fn new(client: Client, endpoints: Vec<String>) -> Result<Self> {
+        if endpoints.is_empty() {
+            return Err(OcsError::ConfigLoad("at least one RPC endpoint is required".to_string()));
+        }
+        Ok(Rpc { client, endpoints, limiter: None })
+    }
+
+    /// Caps requests at `rps` per second; see [`RateLimiter`].
+    pub fn with_rate_limit(mut self, rps: f64) -> Result<Self> {
+        self.limiter = Some(RateLimiter::new(rps)?);
+        Ok(self)
+    }
+}
+
+impl Interface {
+    /// The contract `method` is called on: its own `contract` if set, otherwise the
+    /// interface's.
+    pub fn contract_of<'a>(&'a self, method: &'a Method) -> &'a str {
+        method.contract.as_deref().unwrap_or(&self.contract)
+    }
+
+    /// Checks the interface for definitions that would fail during a run.
+    pub fn validate(&self) -> Result<()> {
+        for (i, method) in self.methods.iter().enumerate() {
+            if method.name.trim().is_empty() {
+                return Err(OcsError::ConfigLoad(format!("method #{}: name must not be empty", i + 1)));
+            }
+            if method.label.trim().is_empty() {
+                return Err(OcsError::ConfigLoad(format!("method '{}': label must not be empty", method.name)));
+            }
+            if method.ou == Some(0) {
+                return Err(OcsError::ConfigLoad(format!("method '{}': ou must be a positive integer", method.name)));
+            }
+            if method.timeout == Some(0) {
+                return Err(OcsError::ConfigLoad(format!("method '{}': timeout must be a positive number of seconds", method.name)));
+            }
+            if method.repeat == Some(0) {
+                return Err(OcsError::ConfigLoad(format!("method '{}': repeat must be a positive integer", method.name)));
+            }
+            if method.amount.is_some_and(|a| a > 0) && method.method_type != MethodType::Call {
+                return Err(OcsError::ConfigLoad(format!("method '{}': amount is only allowed on call methods", method.name)));
+            }
+            if let Some(caller) = &method.caller {
+                if method.method_type != MethodType::View {
+                    return Err(OcsError::ConfigLoad(format!("method '{}': caller is only allowed on view methods", method.name)));
+                }
+                if !is_valid_address(caller) {
+                    return Err(OcsError::ConfigLoad(format!("method '{}': caller {} is not a valid address", method.name, caller)));
+                }
+            }
+            for p in &method.params {
+                if p.name.trim().is_empty() {
+                    return Err(OcsError::ConfigLoad(format!("method '{}': every param needs a name", method.name)));
+                }
+                if !PARAM_TYPES.contains(&p.param_type.as_str()) {
+                    return Err(OcsError::ConfigLoad(format!(
+                        "method '{}': param '{}' has unknown type '{}' (expected one of {})",
+                        method.name, p.name, p.param_type, PARAM_TYPES.join(", ")
+                    )));
+                }
+                let (min, max) = (p.min.unwrap_or(1), p.max.unwrap_or(100));
+                if min > max {
+                    return Err(OcsError::ConfigLoad(format!("method '{}': param '{}' has min {} greater than max {}", method.name, p.name, min, max)));
+                }
+                if p.len.is_some() && p.param_type != "string" {
+                    return Err(OcsError::ConfigLoad(format!("method '{}': param '{}' sets len but is not a string", method.name, p.name)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Signing key plus the address it transacts as.
+///
+/// The account keeps a local nonce counter so consecutive transactions in one run don't
+/// race the node's view of the nonce. It is seeded from [`get_balance`] on first use and
+/// only re-synced after the node rejects a nonce.
+pub struct Account {
+    pub sk: SigningKey,
+    pub addr: String,
+    last_nonce: Option<u64>,
+    nonce_floor: Option<u64>,
+    time_offset_micros: i64,
+}
+
+impl Account {
+    pub fn new(sk: SigningKey, addr: String) -> Self {
+        Account { sk, addr, last_nonce: None, nonce_floor: None, time_offset_micros: 0 }
+    }
+
+    /// Shifts the timestamp of every transaction signed from now on by `micros` (negative
+    /// to go back), e.g. to make up for a local clock that runs ahead of the node's.
+    pub fn set_time_offset(&mut self, micros: i64) {
+        self.time_offset_micros = micros;
+    }
+
+    /// Sets a lower bound for seeding the counter, e.g. the last nonce submitted by a
+    /// previous run that the node may not reflect yet. The larger of the two is used.
+    pub fn set_nonce_floor(&mut self, floor: u64) {
+        self.nonce_floor = Some(floor);
+    }
+
+    /// The nonce the next transaction should use.
+    pub fn next_nonce(&mut self, rpc: &Rpc) -> Result<u64> {
+        let last = match self.last_nonce {
+            Some(n) => n,
+            None => self.seed_nonce(get_balance(rpc, &self.addr)?.nonce),
+        };
+        Ok(last + 1)
+    }
+
+    /// Seeds the counter from the node's nonce, raised to the floor if one is set.
+    pub(crate) fn seed_nonce(&mut self, node_nonce: u64) -> u64 {
+        let n = node_nonce.max(self.nonce_floor.unwrap_or(0));
+        self.last_nonce = Some(n);
+        n
+    }
+
+    /// Seeds the counter from a nonce the caller already fetched, e.g. with the balance,
+    /// to save the first transaction a request; does nothing once the counter is seeded.
+    pub fn prime_nonce(&mut self, node_nonce: u64) {
+        if self.last_nonce.is_none() {
+            self.seed_nonce(node_nonce);
+        }
+    }
+
+    /// The nonce of the last committed transaction, if any since the last resync.
+    pub fn last_nonce(&self) -> Option<u64> {
+        self.last_nonce
+    }
+
+    /// Records `nonce` as used by a successful submission.
+    pub fn commit_nonce(&mut self, nonce: u64) {
+        self.last_nonce = Some(nonce);
+    }
+
+    /// Forgets the local counter so the next transaction re-reads it from the node.
+    /// The floor is dropped too, since the node has just disagreed with it.
+    pub fn resync_nonce(&mut self) {
+        self.last_nonce = None;
+        self.nonce_floor = None;
+    }
+}
+
+/// How often and how patiently a failed request is retried.
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for every further attempt.
+    pub base_delay: Duration,
+    /// Longest wait before a retry, for the backoff as well as a server's `Retry-After`.
+    pub max_delay: Duration,
+    /// How many times [`call_contract_tx`] re-reads the nonce from the node and starts
+    /// over after the retries end on a nonce conflict; 0 disables this.
+    pub nonce_resyncs: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy { max_attempts: 3, base_delay: Duration::from_secs(1), max_delay: Duration::from_secs(60), nonce_resyncs: 0 }
+    }
+}
+
+impl RetryPolicy {
+    /// Exponential backoff for the given 1-based attempt, plus 0–500ms of random jitter,
+    /// at most [`RetryPolicy::max_delay`].
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self.base_delay.saturating_mul(1 << (attempt - 1).min(16));
+        (backoff + Duration::from_millis(rand::thread_rng().gen_range(0..=500))).min(self.max_delay)
+    }
+}
+
+/// Per-run transaction settings.
+pub struct TxOptions {
+    pub dry_run: bool,
+    /// Refuse to sign any transaction whose nonce is below this, so a re-run cannot
+    /// resubmit old nonces.
+    pub min_nonce: Option<u64>,
+    /// Where to record every submitted transaction.
+    pub audit: Option<Arc<AuditLog>>,
+}
+
+/// Gas value used when a method does not set `ou`.
+pub const DEFAULT_OU: u64 = 1;
+
+/// Raw units (micro-OCT) per OCT, unless the wallet sets other [`Wallet::decimals`].
+pub const MICRO_PER_OCT: u64 = 1_000_000;
+
+/// Every [`MethodType`], in the order runs group them.
+pub const METHOD_TYPES: &[MethodType] = &[MethodType::View, MethodType::Call];
+
+/// Values accepted for [`Param::param_type`]; see [`generate_params`].
+pub const PARAM_TYPES: &[&str] = &["number", "address", "bool", "string"];
+
+/// One state-changing contract call to sign and submit.
+pub struct ContractCall<'a> {
+    pub contract: &'a str,
+    pub method: &'a str,
+    pub params: &'a [String],
+    pub ou: u64,
+    /// Micro-OCT sent with the call; 0 for non-payable methods.
+    pub amount: u64,
+    /// Replaces the client's timeout for the submission request.
+    pub timeout: Option<Duration>,
+}
+
+/// The signed part of a transaction; see [`build_signing_blob`] for its canonical form.
+#[derive(Serialize)]
+pub struct Transaction {
+    pub from: String,
+    pub to_: String,
+    pub amount: String,
+    pub nonce: u64,
+    pub ou: String,
+    /// Unix time in integer microseconds, the one canonical form used in both the
+    /// signing blob and the submitted body.
+    pub timestamp: u64,
+}
+
+/// Outcome of a submitted transaction.
+pub struct TxReceipt {
+    pub tx_hash: String,
+    pub nonce: u64,
+    /// Unix time in microseconds, as signed.
+    pub timestamp: u64,
+    /// Full response body returned by `call-contract`.
+    pub raw: serde_json::Value,
+}
+
+/// What [`try_send_tx`] did with a signed transaction.
+pub enum Submission {
+    /// The node accepted it.
+    Sent(TxReceipt),
+    /// `opts.dry_run` was set: the `call-contract` body that would have been sent.
+    DryRun(serde_json::Value),
+}
+
+/// Last status seen by [`wait_for_confirmation`].
+pub struct Confirmation {
+    /// `"confirmed"`, `"pending"`, `"not_found"` or whatever final status the node reported.
+    pub status: String,
+    /// Body of the last `/tx/{hash}` response (`null` if the hash was never found).
+    pub raw: serde_json::Value,
+}
+
+impl Confirmation {
+    pub fn is_confirmed(&self) -> bool {
+        self.status == "confirmed"
+    }
+
+    /// Fee actually charged, in micro-OCT, if the receipt reports one as `fee`, `fee_raw`
+    /// or `fee_paid` (at the top level or under `receipt`).
+    pub fn fee(&self) -> Option<u128> {
+        [&self.raw, &self.raw["receipt"]].into_iter()
+            .flat_map(|obj| ["fee", "fee_raw", "fee_paid"].map(|key| &obj[key]))
+            .find_map(|value| match value {
+                serde_json::Value::Number(n) => n.as_u64().map(u128::from),
+                serde_json::Value::String(s) => parse_micro(s).ok(),
+                _ => None,
+            })
+    }
+}
+
+/// What `/simulate-contract` says a transaction would do; see [`simulate_tx`].
+pub struct Simulation {
+    /// `result` from the response: the method's would-be return value.
+    pub result: serde_json::Value,
+    /// Gas the transaction would use, from `gas_used`, `ou_used` or `gas`.
+    pub gas_used: Option<u64>,
+    /// Full response body.
+    pub raw: serde_json::Value,
+}
+
+impl Simulation {
+    /// Reads a `/simulate-contract` response; an `error` field or a failed `status` means
+    /// the transaction would be rejected.
+    pub(crate) fn from_raw(mut raw: serde_json::Value) -> Result<Self> {
+        if raw["error"].is_string() || matches!(raw["status"].as_str(), Some("error" | "failed" | "reverted")) {
+            let detail = ["error", "message"].iter()
+                .find_map(|key| raw[key].as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| raw.to_string());
+            return Err(OcsError::Rejected(format!("simulation failed ({})", detail)));
+        }
+        let gas_used = ["gas_used", "ou_used", "gas"].iter().find_map(|key| match &raw[key] {
+            serde_json::Value::Number(n) => n.as_u64(),
+            serde_json::Value::String(s) => s.parse().ok(),
+            _ => None,
+        });
+        Ok(Simulation { result: raw["result"].take(), gas_used, raw })
+    }
+}
+
+/// Network identity reported by the node; see [`get_network`].
+pub struct NetworkInfo {
+    /// `chain_id`, `chainId` or `network_id` from the response.
+    pub chain_id: Option<String>,
+    /// `name` or `network` from the response.
+    pub name: Option<String>,
+    pub raw: serde_json::Value,
+}
+
+impl NetworkInfo {
+    pub(crate) fn from_raw(raw: serde_json::Value) -> Self {
+        let field = |keys: &[&str]| keys.iter().find_map(|key| match &raw[key] {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        });
+        NetworkInfo {
+            chain_id: field(&["chain_id", "chainId", "network_id"]),
+            name: field(&["name", "network"]),
+            raw,
+        }
+    }
+
+    /// Whether `expected` is this network's chain ID or name, ignoring case.
+    pub fn matches(&self, expected: &str) -> bool {
+        [&self.chain_id, &self.name].into_iter().flatten().any(|v| v.eq_ignore_ascii_case(expected))
+    }
+}
+
+impl std::fmt::Display for NetworkInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.name, &self.chain_id) {
+            (Some(name), Some(id)) => write!(f, "{} (chain ID {})", name, id),
+            (Some(name), None) => write!(f, "{}", name),
+            (None, Some(id)) => write!(f, "chain ID {}", id),
+            (None, None) => write!(f, "unknown ({})", self.raw),
+        }
+    }
+}
+
+/// Response body of `GET {rpc}/balance/{addr}`.
+#[derive(Deserialize)]
+pub struct BalanceResponse {
+    pub balance_raw: String,
+    pub nonce: u64,
+}
+
+/// A wallet balance as returned by [`get_balance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Balance {
+    /// Exact balance in micro-OCT.
+    pub micro: u128,
+    pub nonce: u64,
+}
+
+impl Balance {
+    /// The balance in OCT at the network's precision, for display only; use
+    /// [`Balance::micro`] for arithmetic.
+    pub fn oct(&self, denomination: &Denomination) -> f64 {
+        denomination.to_oct(self.micro)
+    }
+}
+
+/// How many raw units make one OCT, for converting amounts to and from what users type
+/// and read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Denomination {
+    pub units_per_oct: u128,
+}
+
+impl Default for Denomination {
+    fn default() -> Self {
+        Denomination { units_per_oct: MICRO_PER_OCT as u128 }
+    }
+}
+
+impl Denomination {
+    /// The denomination of a token with `decimals` decimal places.
+    pub fn from_decimals(decimals: u32) -> Result<Self> {
+        10u128
+            .checked_pow(decimals)
+            .map(|units_per_oct| Denomination { units_per_oct })
+            .ok_or_else(|| OcsError::ConfigLoad(format!("decimals {} is too large", decimals)))
+    }
+
+    /// A raw amount in OCT, for display only.
+    pub fn to_oct(&self, raw: u128) -> f64 {
+        raw as f64 / self.units_per_oct as f64
+    }
+
+    /// An OCT amount, e.g. from the command line, in raw units, rounded to the nearest unit.
+    pub fn from_oct(&self, oct: f64) -> u128 {
+        (oct * self.units_per_oct as f64).round() as u128
+    }
+}
+
+// =============================
+// API Errors
+// =============================
+/// Everything the library can fail with. `main` turns these into `anyhow` errors.
+#[derive(Debug, thiserror::Error)]
+pub enum OcsError {
+    /// The request could not be sent or its response could not be read.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// The node answered with an error status.
+    #[error(transparent)]
+    Api(#[from] ApiError),
+    /// A response that is not the JSON we expected.
+    #[error("invalid response: {0}")]
+    InvalidResponse(String),
+    /// A view call that did not report `"status": "success"`.
+    #[error("view call failed: {0}")]
+    View(String),
+    /// A submitted transaction the node did not accept.
+    #[error("transaction rejected: {0}")]
+    Rejected(String),
+    /// A submission whose response did not arrive in time. The node may still have
+    /// applied it, so it is never retried.
+    #[error("submission with nonce {nonce} timed out; it may still have been applied, so it was not retried")]
+    SubmitTimedOut {
+        nonce: u64,
+        #[source]
+        source: reqwest::Error,
+    },
+    /// The next nonce is below [`TxOptions::min_nonce`]; nothing was signed.
+    #[error("nonce {nonce} is below the minimum nonce {min}")]
+    NonceBelowMinimum { nonce: u64, min: u64 },
+    #[error("signing failed: {0}")]
+    Signing(String),
+    /// A private key that could not be decrypted or decoded.
+    #[error("{0}")]
+    KeyDecode(String),
+    #[error("{0}")]
+    BalanceParse(String),
+    /// Invalid configuration: interface definitions, endpoints, rate limits.
+    #[error("{0}")]
+    ConfigLoad(String),
+    /// The [`AuditLog`] could not be read or written.
+    #[error("cannot write the audit file")]
+    Audit(#[source] std::io::Error),
+    /// Wraps the error that a retry loop did not retry.
+    #[error("permanent failure, not retried")]
+    Permanent(#[source] Box<OcsError>),
+    /// Wraps the last error of a retry loop that ran out of attempts.
+    #[error("transient failure, gave up after {attempts} attempts")]
+    GaveUp {
+        attempts: u32,
+        #[source]
+        source: Box<OcsError>,
+    },
+}
+
+impl From<serde_json::Error> for OcsError {
+    fn from(e: serde_json::Error) -> Self {
+        OcsError::InvalidResponse(e.to_string())
+    }
+}
+
+impl OcsError {
+    /// Whether the node rejected a transaction because of its nonce.
+    pub fn is_nonce_conflict(&self) -> bool {
+        match self.root() {
+            OcsError::Api(api) => api.is_nonce_error(),
+            OcsError::Rejected(detail) => detail.to_lowercase().contains("nonce"),
+            _ => false,
+        }
+    }
+
+    /// The underlying error, with any [`OcsError::Permanent`] or [`OcsError::GaveUp`] wrapper removed.
+    pub fn root(&self) -> &OcsError {
+        match self {
+            OcsError::Permanent(inner) | OcsError::GaveUp { source: inner, .. } => inner.root(),
+            other => other,
+        }
+    }
+}
+
+pub type Result<T, E = OcsError> = std::result::Result<T, E>;
+
+/// An HTTP error response from the node.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: u16,
+    /// Full URL of the failed request.
+    pub endpoint: String,
+    /// The response body parsed as JSON, or the raw text as a JSON string.
+    pub body: serde_json::Value,
+    /// The response's `Retry-After`, if it gave one in seconds.
+    pub retry_after: Option<Duration>,
+}
+
+impl ApiError {
+    pub fn new(status: u16, endpoint: &str, text: String) -> Self {
+        let body = serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text));
+        ApiError { status, endpoint: endpoint.to_string(), body, retry_after: None }
+    }
+
+    /// Sets [`ApiError::retry_after`] from the response headers.
+    pub(crate) fn with_retry_after(mut self, headers: &reqwest::header::HeaderMap) -> Self {
+        self.retry_after = retry_after(headers);
+        self
+    }
+
+    /// Whether the node rejected the request because of its nonce.
+    pub fn is_nonce_error(&self) -> bool {
+        self.body.to_string().to_lowercase().contains("nonce")
+    }
+
+    /// Recognizes the common rejections from the wording of the body.
+    pub fn kind(&self) -> Option<ApiErrorKind> {
+        let text = self.body.to_string().to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| text.contains(w));
+        if has(&["insufficient"]) && has(&["balance", "funds"]) {
+            Some(ApiErrorKind::InsufficientBalance)
+        } else if self.is_nonce_error() {
+            Some(ApiErrorKind::InvalidNonce)
+        } else if has(&["signature"]) {
+            Some(ApiErrorKind::BadSignature)
+        } else if has(&["method"]) && has(&["unknown", "not found", "no such", "does not exist"]) {
+            Some(ApiErrorKind::UnknownMethod)
+        } else {
+            None
+        }
+    }
+
+    /// Whether sending the same request again may succeed: 429s, 5xx and nonce conflicts
+    /// (the nonce is re-synced before the retry) are retryable, any other 4xx is not.
+    pub fn is_retryable(&self) -> bool {
+        self.status == 429 || self.status >= 500 || self.is_nonce_error()
+    }
+}
+
+/// A rejection [`ApiError::kind`] recognizes, with a suggested fix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiErrorKind {
+    InsufficientBalance,
+    InvalidNonce,
+    BadSignature,
+    UnknownMethod,
+}
+
+impl ApiErrorKind {
+    pub fn summary(self) -> &'static str {
+        match self {
+            ApiErrorKind::InsufficientBalance => "insufficient balance",
+            ApiErrorKind::InvalidNonce => "invalid nonce",
+            ApiErrorKind::BadSignature => "bad signature",
+            ApiErrorKind::UnknownMethod => "unknown method",
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            ApiErrorKind::InsufficientBalance => "fund the wallet, or lower the method's ou or amount",
+            ApiErrorKind::InvalidNonce => {
+                "the nonce is out of sync, e.g. another client used this wallet; run again, or pass --continue-on-nonce-error"
+            }
+            ApiErrorKind::BadSignature => {
+                "check that the private key matches addr (see whoami) and that the clock is right (see --time-offset)"
+            }
+            ApiErrorKind::UnknownMethod => "check the method name in the interface file and the contract address",
+        }
+    }
+}
+
+/// Recognized rejections are shown as a summary and a hint; the raw body is added only
+/// when request logging (`-v`) is on. Anything else shows the body as is.
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let body = match &self.body {
+            serde_json::Value::String(text) => text.clone(),
+            body => body.to_string(),
+        };
+        match self.kind() {
+            Some(kind) => {
+                write!(f, "{} (api error {} from {}): {}", kind.summary(), self.status, self.endpoint, kind.hint())?;
+                if log::log_enabled!(log::Level::Info) {
+                    write!(f, "; node said: {}", body)?;
+                }
+                Ok(())
+            }
+            None => write!(f, "api error {} from {}: {}", self.status, self.endpoint, body),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Parses a `Retry-After` header given in seconds; the HTTP-date form is not supported
+/// and falls back to the normal backoff, like a missing header.
+pub(crate) fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    value.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// Whether a failed request is worth retrying: timeouts, connection errors and
+/// [retryable](ApiError::is_retryable) API errors are transient; everything else
+/// (4xx rejections, malformed responses, signing errors, timed-out submissions) is
+/// permanent.
+pub fn is_transient(e: &OcsError) -> bool {
+    match e {
+        OcsError::Api(api) => api.is_retryable(),
+        OcsError::Http(http) => http.is_timeout() || http.is_connect() || http.is_request(),
+        _ => false,
+    }
+}
+
+// =============================
+// Rate Limiting
+// =============================
+/// A token bucket holding up to one second's worth of requests.
+///
+/// [`RateLimiter::reserve`] takes a token and returns how long the caller must wait
+/// before sending; when the bucket is empty, callers queue up rather than fail.
+pub struct RateLimiter {
+    rps: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(rps: f64) -> Result<Self> {
+        if !(rps.is_finite() && rps > 0.0) {
+            return Err(OcsError::ConfigLoad(format!("rate limit must be a positive number of requests per second, got {}", rps)));
+        }
+        Ok(RateLimiter {
+            rps,
+            state: Mutex::new(BucketState { tokens: rps.max(1.0), last: Instant::now() }),
+        })
+    }
+
+    /// Takes one token and returns the delay before the request may be sent.
+    pub fn reserve(&self) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let refill = now.duration_since(state.last).as_secs_f64() * self.rps;
+        state.tokens = (state.tokens + refill).min(self.rps.max(1.0)) - 1.0;
+        state.last = now;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rps)
+        }
+    }
+
+    /// Blocks the current thread until a request may be sent.
+    pub fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            log::debug!("rate limit: waiting {}ms", wait.as_millis());
+            std::thread::sleep(wait);
+        }
+    }
+}
+
+// =============================
+// Wallet Encryption
+// =============================
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+fn wallet_cipher(passphrase: &str, salt: &[u8]) -> Result<Aes256Gcm> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| OcsError::KeyDecode(format!("key derivation failed: {}", e)))?;
+    Aes256Gcm::new_from_slice(key.as_ref()).map_err(|e| OcsError::KeyDecode(e.to_string()))
+}
+
+/// Encrypts raw private key bytes for an `"enc": true` wallet.
+///
+/// The result is base64 of `salt (16 bytes) || nonce (12 bytes) || AES-256-GCM ciphertext`,
+/// with the AES key derived from `passphrase` and the salt using Argon2id.
+pub fn encrypt_private_key(key_bytes: &[u8], passphrase: &str) -> Result<String> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let ciphertext = wallet_cipher(passphrase, &salt)?
+        .encrypt(Nonce::from_slice(&nonce), key_bytes)
+        .map_err(|_| OcsError::KeyDecode("failed to encrypt private key".to_string()))?;
+
+    let mut out = salt.to_vec();
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(general_purpose::STANDARD.encode(out))
+}
+
+/// Decodes a plain (not encrypted) private key.
+///
+/// The expected format is base64, but 64 hex digits (optionally `0x`-prefixed) are accepted
+/// too. Other near misses, such as hex of the wrong length, get a targeted message rather
+/// than a bare base64 error.
+pub fn decode_private_key(encoded: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let trimmed = encoded.trim();
+    let hex = trimmed.strip_prefix("0x").or_else(|| trimmed.strip_prefix("0X"));
+    let digits = hex.unwrap_or(trimmed);
+    let is_hex = !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_hexdigit());
+    if is_hex && digits.len() == 64 {
+        let bytes = (0..64).step_by(2)
+            .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).expect("checked hex digits"))
+            .collect();
+        return Ok(Zeroizing::new(bytes));
+    }
+    if hex.is_some() || (is_hex && digits.len() > 44) {
+        return Err(OcsError::KeyDecode(format!(
+            "key looks like hex but has {} digits; expected 64 hex digits (32 bytes), or base64",
+            digits.len()
+        )));
+    }
+    general_purpose::STANDARD.decode(trimmed).map(Zeroizing::new).map_err(|e| {
+        OcsError::KeyDecode(format!("private key is not valid base64 ({}); expected the base64 of a 32-byte ed25519 key", e))
+    })
+}
+
+/// Reverses [`encrypt_private_key`]. The returned bytes are wiped when dropped.
+pub fn decrypt_private_key(encoded: &str, passphrase: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let data = general_purpose::STANDARD.decode(encoded.trim())
+        .map_err(|e| OcsError::KeyDecode(format!("encrypted private key is not base64: {}", e)))?;
+    if data.len() <= SALT_LEN + NONCE_LEN {
+        return Err(OcsError::KeyDecode("encrypted private key is too short".to_string()));
+    }
+    let (salt, rest) = data.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let plain = wallet_cipher(passphrase, salt)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| OcsError::KeyDecode("failed to decrypt private key (wrong passphrase?)".to_string()))?;
+    Ok(Zeroizing::new(plain))
+}
+
+// =============================
+// HD Keys
+// =============================
+/// Derivation path used when a wallet's `mnemonic` has no `derivation_path`: the master key.
+pub const DEFAULT_DERIVATION_PATH: &str = "m";
+
+/// Derives an ed25519 private key from a BIP-39 `phrase` (English words, no passphrase)
+/// along a SLIP-0010 `path` such as `m/44'/0'/0'`.
+pub fn key_from_mnemonic(phrase: &str, path: &str) -> Result<Zeroizing<[u8; 32]>> {
+    let mnemonic = bip39::Mnemonic::parse(phrase)
+        .map_err(|e| OcsError::KeyDecode(format!("invalid mnemonic: {}", e)))?;
+    let seed = Zeroizing::new(mnemonic.to_seed(""));
+    derive_ed25519_key(seed.as_slice(), path)
+}
+
+/// SLIP-0010 ed25519 derivation from a BIP-32 seed. Ed25519 only has hardened children, so
+/// every path segment must end in `'` (or `h`).
+pub fn derive_ed25519_key(seed: &[u8], path: &str) -> Result<Zeroizing<[u8; 32]>> {
+    use hmac::{Hmac, Mac};
+    type HmacSha512 = Hmac<sha2::Sha512>;
+
+    let invalid = |why: &str| OcsError::KeyDecode(format!("invalid derivation path {:?}: {}", path, why));
+    let mut segments = path.trim().split('/');
+    if segments.next() != Some("m") {
+        return Err(invalid("must start with m"));
+    }
+
+    let hmac = |key: &[u8], data: &[&[u8]]| {
+        let mut mac = <HmacSha512 as Mac>::new_from_slice(key).expect("HMAC takes keys of any length");
+        for part in data {
+            mac.update(part);
+        }
+        Zeroizing::new(<[u8; 64]>::from(mac.finalize().into_bytes()))
+    };
+    let mut node = hmac(b"ed25519 seed", &[seed]);
+    for segment in segments {
+        let index = segment.strip_suffix(['\'', 'h', 'H'])
+            .ok_or_else(|| invalid("ed25519 only supports hardened segments such as 0'"))?
+            .parse::<u32>().ok()
+            .filter(|i| *i < 1 << 31)
+            .ok_or_else(|| invalid(&format!("bad segment {:?}", segment)))?;
+        let (key, chain_code) = node.split_at(32);
+        node = hmac(chain_code, &[&[0], key, &(index | 1 << 31).to_be_bytes()]);
+    }
+    let mut key = Zeroizing::new([0; 32]);
+    key.copy_from_slice(&node[..32]);
+    Ok(key)
+}
+
+// =============================
+// Address Derivation
+// =============================
+/// The Octra address for a public key: `oct` followed by the base58 SHA-256 of the key.
+pub fn derive_address(vk: &VerifyingKey) -> String {
+    format!("oct{}", bs58::encode(Sha256::digest(vk.as_bytes())).into_string())
+}
+
+/// Whether `addr` has the shape of an Octra address: `oct` plus the base58 of 32 bytes.
+pub fn is_valid_address(addr: &str) -> bool {
+    addr.strip_prefix("oct")
+        .and_then(|rest| bs58::decode(rest).into_vec().ok())
+        .is_some_and(|bytes| bytes.len() == 32)
+}
+
+// =============================
+// Helper: API Call
+// =============================
+/// Sends a `GET` or `POST` (JSON body) request for `path` to each endpoint in turn and
+/// returns the first response that is not a 5xx.
+///
+/// Connection errors and 5xx responses move on to the next endpoint, except that a
+/// timed-out `/call-contract` is returned at once; if every endpoint fails, the last
+/// failure is returned. Requests are traced through the `log` crate:
+/// method, URL and status at `info`, bodies (with `public_key` redacted) at `debug`.
+pub fn send_request(
+    rpc: &Rpc,
+    method: &str,
+    path: &str,
+    data: Option<&serde_json::Value>
+) -> Result<Response> {
+    send_request_with_timeout(rpc, method, path, data, None)
+}
+
+/// Like [`send_request`], but with `timeout` replacing the client's timeout for this request.
+pub fn send_request_with_timeout(
+    rpc: &Rpc,
+    method: &str,
+    path: &str,
+    data: Option<&serde_json::Value>,
+    timeout: Option<Duration>
+) -> Result<Response> {
+    let mut last_err = None;
+    for base in &rpc.endpoints {
+        let url = format!("{}{}", base, path);
+        let mut req = match method {
+            "GET" => rpc.client.get(&url),
+            "POST" => rpc.client.post(&url).json(&data),
+            _ => return Err(OcsError::ConfigLoad(format!("unsupported HTTP method {}", method))),
+        };
+        if let Some(timeout) = timeout {
+            req = req.timeout(timeout);
+        }
+        if let Some(limiter) = &rpc.limiter {
+            limiter.acquire();
+        }
+        log::info!("→ {} {}", method, url);
+        if let Some(body) = data {
+            log::debug!("request body: {}", redact(body));
+        }
+
+        let sent = Instant::now();
+        match req.send() {
+            Ok(resp) if resp.status().is_server_error() => {
+                let status = resp.status().as_u16();
+                let headers = resp.headers().clone();
+                let text = resp.text()?;
+                log::info!("← {} {} ({} ms)", status, url, sent.elapsed().as_millis());
+                log::debug!("response body: {}", text);
+                last_err = Some(ApiError::new(status, &url, text).with_retry_after(&headers).into());
+            }
+            Ok(resp) => {
+                log::info!("← {} {} ({} ms)", resp.status().as_u16(), url, sent.elapsed().as_millis());
+                return Ok(resp);
+            }
+            // A submission that timed out may have reached the node; sending it to the
+            // next endpoint could apply it twice.
+            Err(e) if e.is_timeout() && path == "/call-contract" => return Err(e.into()),
+            Err(e) => last_err = Some(e.into()),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| OcsError::ConfigLoad("no RPC endpoints configured".to_string())))
+}
+
+/// Like [`send_request`], but decodes the JSON response.
+///
+/// Any HTTP status of 400 or above is returned as an [`ApiError`].
+pub fn api_call<T: for<'de> Deserialize<'de>>(
+    rpc: &Rpc,
+    method: &str,
+    path: &str,
+    data: Option<serde_json::Value>
+) -> Result<T> {
+    api_call_with_timeout(rpc, method, path, data, None)
+}
+
+/// Like [`api_call`], but with a per-request timeout; see [`send_request_with_timeout`].
+pub fn api_call_with_timeout<T: for<'de> Deserialize<'de>>(
+    rpc: &Rpc,
+    method: &str,
+    path: &str,
+    data: Option<serde_json::Value>,
+    timeout: Option<Duration>
+) -> Result<T> {
+    let resp = send_request_with_timeout(rpc, method, path, data.as_ref(), timeout)?;
+    let (status, url, headers) = (resp.status().as_u16(), resp.url().to_string(), resp.headers().clone());
+    let text = resp.text()?;
+    log::debug!("response body: {}", text);
+
+    if status >= 400 {
+        return Err(ApiError::new(status, &url, text).with_retry_after(&headers).into());
+    }
+
+    Ok(serde_json::from_str(&text)?)
+}
+
+/// Copy of a request body that is safe to log.
+pub(crate) fn redact(body: &serde_json::Value) -> serde_json::Value {
+    let mut body = body.clone();
+    if let Some(key) = body.get_mut("public_key") {
+        *key = json!("<redacted>");
+    }
+    body
+}
+
+// =============================
+// Helper: Balance
+// =============================
+/// Fetches `GET /balance/{addr}` and returns the exact balance and current nonce.
+///
+/// The node is expected to answer with `{"balance_raw": "<micro-OCT>", "nonce": <u64>}`.
+pub fn get_balance(rpc: &Rpc, addr: &str) -> Result<Balance> {
+    let balance: BalanceResponse = api_call(
+        rpc,
+        "GET",
+        &format!("/balance/{}", addr),
+        None
+    )?;
+    balance.parse()
+}
+
+impl BalanceResponse {
+    pub(crate) fn parse(&self) -> Result<Balance> {
+        let micro = parse_micro(&self.balance_raw).map_err(|e| {
+            OcsError::BalanceParse(format!("invalid balance_raw {:?}: {}", self.balance_raw, e))
+        })?;
+        Ok(Balance { micro, nonce: self.nonce })
+    }
+}
+
+/// Parses an integer amount of micro-units without going through a float.
+///
+/// Accepts `_` digit separators and exact scientific notation such as `"1.5e6"`; a value
+/// that would leave a fractional micro-unit is rejected.
+pub fn parse_micro(raw: &str) -> Result<u128> {
+    let cleaned: String = raw.trim().chars().filter(|&c| c != '_').collect();
+    let (mantissa, exponent) = match cleaned.split_once(['e', 'E']) {
+        Some((m, e)) => (m, e.parse::<i32>().map_err(|_| OcsError::BalanceParse("bad exponent".to_string()))?),
+        None => (cleaned.as_str(), 0),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{}{}", int_part, frac_part);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(OcsError::BalanceParse("not a non-negative number".to_string()));
+    }
+
+    let scale = i32::try_from(frac_part.len()).ok()
+        .and_then(|len| exponent.checked_sub(len))
+        .ok_or_else(|| OcsError::BalanceParse("out of range".to_string()))?;
+    let digits = if scale > 39 {
+        return Err(OcsError::BalanceParse("out of range".to_string()));
+    } else if scale >= 0 {
+        format!("{}{}", digits, "0".repeat(scale as usize))
+    } else {
+        let keep = digits.len().saturating_sub(scale.unsigned_abs() as usize);
+        if digits[keep..].bytes().any(|b| b != b'0') {
+            return Err(OcsError::BalanceParse("fractional micro-units".to_string()));
+        }
+        digits[..keep].to_string()
+    };
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    digits.parse::<u128>().map_err(|_| OcsError::BalanceParse("out of range".to_string()))
+}
+
+// =============================
+// Helper: Network
+// =============================
+/// Asks the node which network it serves via `GET /network`, falling back to `GET /info`.
+///
+/// Returns `None` when the node has neither endpoint (both answer 404).
+pub fn get_network(rpc: &Rpc) -> Result<Option<NetworkInfo>> {
+    for path in ["/network", "/info"] {
+        match api_call(rpc, "GET", path, None) {
+            Ok(raw) => return Ok(Some(NetworkInfo::from_raw(raw))),
+            Err(e) if is_not_found(&e) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(None)
+}
+
+// =============================
+// Helper: TX Signing
+// =============================
+/// Version of the blob format produced by [`build_signing_blob`]. Bump it, and keep the
+/// previous builder around, if the node ever changes its canonical form.
+pub const BLOB_VERSION: u32 = 1;
+
+/// Signs [`build_signing_blob`] for `tx` and returns the base64 ed25519 signature.
+pub fn sign_tx(sk: &SigningKey, tx: &Transaction) -> Result<String> {
+    let blob = build_signing_blob(tx);
+    let sig = sk.sign(blob.as_bytes());
+    Ok(general_purpose::STANDARD.encode(sig.to_bytes()))
+}
+
+/// Builds the exact bytes that are signed, in format [`BLOB_VERSION`] 1.
+///
+/// The blob is a compact JSON object with no whitespace and the keys in this order:
+///
+/// | key         | JSON type | value                                   |
+/// |-------------|-----------|-----------------------------------------|
+/// | `from`      | string    | sender address                          |
+/// | `to_`       | string    | contract address                        |
+/// | `amount`    | string    | micro-OCT as a decimal integer, e.g. `"0"` |
+/// | `nonce`     | number    | integer                                 |
+/// | `ou`        | string    | decimal integer, e.g. `"1"`             |
+/// | `timestamp` | number    | integer Unix microseconds               |
+///
+/// Strings are escaped as by `serde_json`. The node rebuilds this blob from the submitted
+/// fields, so any change here invalidates every signature.
+pub fn build_signing_blob(tx: &Transaction) -> String {
+    let string = |s: &str| serde_json::Value::from(s).to_string();
+    format!(
+        r#"{{"from":{},"to_":{},"amount":{},"nonce":{},"ou":{},"timestamp":{}}}"#,
+        string(&tx.from), string(&tx.to_), string(&tx.amount), tx.nonce, string(&tx.ou), tx.timestamp
+    )
+}
+
+/// Checks that the base64 signature `sig` verifies for `blob` under `sk`'s public key.
+pub fn verify_signature(sk: &SigningKey, blob: &[u8], sig: &str) -> Result<()> {
+    let bytes = general_purpose::STANDARD.decode(sig)
+        .map_err(|e| OcsError::Signing(format!("signature is not base64: {}", e)))?;
+    let signature = Signature::from_slice(&bytes).map_err(|e| OcsError::Signing(e.to_string()))?;
+    sk.verifying_key()
+        .verify_strict(blob, &signature)
+        .map_err(|e| OcsError::Signing(format!("signature does not verify against the signed blob: {}", e)))
+}
+
+// =============================
+// Retries
+// =============================
+/// Runs `op` until it succeeds, retrying [transient](is_transient) failures with
+/// exponential backoff per `retry`.
+///
+/// A permanent failure is returned at once; the returned error says which of the two
+/// ended the loop. Used for both view calls and transactions.
+pub fn with_retries<T>(retry: &RetryPolicy, mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(e) => std::thread::sleep(next_retry(retry, attempt, e)?),
+        }
+        attempt += 1;
+    }
+}
+
+/// Decides what a retry loop does after its `attempt`-th failure: the delay before the
+/// next attempt, or the error to give up with.
+pub(crate) fn next_retry(retry: &RetryPolicy, attempt: u32, e: OcsError) -> Result<Duration> {
+    if !is_transient(&e) {
+        return Err(OcsError::Permanent(Box::new(e)));
+    }
+    if attempt >= retry.max_attempts {
+        return Err(OcsError::GaveUp { attempts: attempt, source: Box::new(e) });
+    }
+    // The server's Retry-After replaces our own backoff, so we never retry sooner than asked,
+    // but a node asking for more than max_delay does not stall the run for that long.
+    let delay = match &e {
+        OcsError::Api(api) => api.retry_after.map_or_else(|| retry.delay(attempt), |wait| wait.min(retry.max_delay)),
+        _ => retry.delay(attempt),
+    };
+    eprintln!("⚠ Attempt {}/{} failed: {} (retrying in {}ms)", attempt, retry.max_attempts, e, delay.as_millis());
+    Ok(delay)
+}
+
+// =============================
+// View Call
+// =============================
+/// Calls a read-only contract method via `POST /contract/call-view`.
+///
+/// The request body is `{"contract", "method", "params", "caller"}`. The node answers with
+/// `{"status": "success", "result": ...}` and `result` is returned as is, so numbers, arrays
+/// and objects keep their type; any other status is returned as an error.
+pub fn view_call(rpc: &Rpc, contract: &str, method: &str, params: &[String], caller: &str) -> Result<serde_json::Value> {
+    view_call_with_timeout(rpc, contract, method, params, caller, None)
+}
+
+/// Like [`view_call`], but returns the result as a string, or `"null"` if it is not one.
+pub fn view_call_string(rpc: &Rpc, contract: &str, method: &str, params: &[String], caller: &str) -> Result<String> {
+    let result = view_call(rpc, contract, method, params, caller)?;
+    Ok(result.as_str().unwrap_or("null").to_string())
+}
+
+/// Like [`view_call`], but with a per-request timeout, e.g. [`Method::timeout`].
+pub fn view_call_with_timeout(rpc: &Rpc, contract: &str, method: &str, params: &[String], caller: &str, timeout: Option<Duration>) -> Result<serde_json::Value> {
+    let res: serde_json::Value = api_call_with_timeout(
+        rpc,
+        "POST",
+        "/contract/call-view",
+        Some(view_body(contract, method, params, caller)),
+        timeout
+    )?;
+    view_result(res)
+}
+
+pub(crate) fn view_body(contract: &str, method: &str, params: &[String], caller: &str) -> serde_json::Value {
+    json!({
+        "contract": contract,
+        "method": method,
+        "params": params,
+        "caller": caller
+    })
+}
+
+pub(crate) fn view_result(mut res: serde_json::Value) -> Result<serde_json::Value> {
+    if res["status"] == "success" {
+        Ok(res["result"].take())
+    } else {
+        Err(OcsError::View(res.to_string()))
+    }
+}
+
+// =============================
+// Parallel View Calls
+// =============================
+/// Runs `(method, params, timeout)` view calls on up to `concurrency` threads, each retried
+/// per `retry` and sent with its own timeout (e.g. [`Method::request_timeout`]), and returns
+/// the results in the same order as `calls`.
+pub fn view_calls_parallel(rpc: &Rpc, contract: &str, calls: &[(String, Vec<String>, Option<Duration>)], caller: &str, concurrency: usize, retry: &RetryPolicy) -> Vec<Result<serde_json::Value>> {
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.clamp(1, calls.len().max(1)) {
+            let tx = tx.clone();
+            let next = &next;
+            scope.spawn(move || {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some((method, params, timeout)) = calls.get(i) else { break };
+                    let result = with_retries(retry, || view_call_with_timeout(rpc, contract, method, params, caller, *timeout));
+                    let _ = tx.send((i, result));
+                }
+            });
+        }
+    });
+    drop(tx);
+
+    let mut results: Vec<Option<Result<serde_json::Value>>> = calls.iter().map(|_| None).collect();
+    for (i, result) in rx {
+        results[i] = Some(result);
+    }
+    results.into_iter()
+        .map(|r| r.unwrap_or_else(|| Err(OcsError::View("call did not run".to_string()))))
+        .collect()
+}
+
+// =============================
+// Batch View Calls
+// =============================
+/// Sends all `(method, params)` view calls in one `POST /contract/call-view-batch` and
+/// returns the results in the same order as `calls`.
+///
+/// The body is an array of [`view_call`] bodies. The node answers with an array of view
+/// responses (or `{"results": [...]}`), matched to `calls` by index. If the node has no
+/// batch endpoint (404), the calls are made one by one instead.
+///
+/// The single request uses the client's timeout; per-method timeouts do not apply.
+pub fn batch_view_call(rpc: &Rpc, contract: &str, calls: &[(String, Vec<String>)], caller: &str) -> Result<Vec<Result<serde_json::Value>>> {
+    let body = batch_body(contract, calls, caller);
+    match api_call(rpc, "POST", "/contract/call-view-batch", Some(body)) {
+        Ok(res) => batch_results(res, calls.len()),
+        Err(e) if is_not_found(&e) => {
+            log::info!("no batch view endpoint, falling back to single view calls");
+            Ok(calls.iter().map(|(method, params)| view_call(rpc, contract, method, params, caller)).collect())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub(crate) fn batch_body(contract: &str, calls: &[(String, Vec<String>)], caller: &str) -> serde_json::Value {
+    calls.iter().map(|(method, params)| view_body(contract, method, params, caller)).collect()
+}
+
+pub(crate) fn batch_results(res: serde_json::Value, expected: usize) -> Result<Vec<Result<serde_json::Value>>> {
+    let items = match res {
+        serde_json::Value::Array(items) => items,
+        mut res => match res["results"].take() {
+            serde_json::Value::Array(items) => items,
+            _ => return Err(OcsError::InvalidResponse(format!("unexpected batch view response: {}", res))),
+        },
+    };
+    if items.len() != expected {
+        return Err(OcsError::InvalidResponse(format!("batch view returned {} results for {} calls", items.len(), expected)));
+    }
+    Ok(items.into_iter().map(view_result).collect())
+}
+
+pub(crate) fn is_not_found(e: &OcsError) -> bool {
+    matches!(e, OcsError::Api(api) if api.status == 404)
+}
+
+// =============================
+// TX Call with Retry
+// =============================
+/// Submits a state-changing contract call, retried per `retry` with [`with_retries`].
+///
+/// If the retries end on a [nonce conflict](OcsError::is_nonce_conflict), e.g. because
+/// another process is using the account, the nonce is re-read from the node and the
+/// whole sequence starts over, up to `retry.nonce_resyncs` times.
+///
+/// See [`try_send_tx`] for the request format and the dry-run case.
+pub fn call_contract_tx(rpc: &Rpc, account: &mut Account, call: &ContractCall, opts: &TxOptions, retry: &RetryPolicy) -> Result<Submission> {
+    let mut resyncs = 0;
+    loop {
+        match with_retries(retry, || try_send_tx(rpc, account, call, opts)) {
+            Err(e) if e.is_nonce_conflict() && resyncs < retry.nonce_resyncs => {
+                resyncs += 1;
+                note_nonce_resync(account, resyncs, retry);
+            }
+            result => return result,
+        }
+    }
+}
+
+pub(crate) fn note_nonce_resync(account: &mut Account, resyncs: u32, retry: &RetryPolicy) {
+    account.resync_nonce();
+    eprintln!("⚠ Nonce conflict; re-reading the nonce from the node and trying again ({}/{})", resyncs, retry.nonce_resyncs);
+}
+
+/// Signs and submits a single transaction via `POST /call-contract`.
+///
+/// The nonce comes from [`Account::next_nonce`] and is committed once the node accepts
+/// the transaction; an error mentioning the nonce resets the counter. The body carries
+/// `contract`, `method`, `params`, `caller`, `nonce`, `ou`, `timestamp`, the base64 `signature`
+/// and the base64 `public_key`; the node responds with `{"tx_hash": "..."}`, which is
+/// returned as a [`TxReceipt`] together with the nonce and timestamp that were signed.
+/// A response with a missing or empty `tx_hash` is reported as a rejection.
+/// With `opts.dry_run` set nothing is sent and the signed body is returned as
+/// [`Submission::DryRun`] instead.
+pub fn try_send_tx(rpc: &Rpc, account: &mut Account, call: &ContractCall, opts: &TxOptions) -> Result<Submission> {
+    let nonce = account.next_nonce(rpc)?;
+    check_min_nonce(nonce, opts)?;
+    let (timestamp, blob, body) = call_body(account, nonce, call)?;
+
+    if opts.dry_run {
+        account.commit_nonce(nonce);
+        return Ok(Submission::DryRun(body));
+    }
+
+    if let Some(audit) = &opts.audit {
+        audit.record_signed(&blob, &body)?;
+    }
+    let res = api_call_with_timeout(rpc, "POST", "/call-contract", Some(body.clone()), call.timeout);
+    if let Some(audit) = &opts.audit {
+        audit.record_response(&body, &res);
+    }
+    finish_tx(account, nonce, timestamp, res).map(Submission::Sent)
+}
+
+pub(crate) fn check_min_nonce(nonce: u64, opts: &TxOptions) -> Result<()> {
+    match opts.min_nonce {
+        Some(min) if nonce < min => Err(OcsError::NonceBelowMinimum { nonce, min }),
+        _ => Ok(()),
+    }
+}
+
+/// Signs a transaction for `nonce` and builds the `call-contract` body around it.
+/// Returns the body together with the timestamp and the blob that were signed.
+pub(crate) fn call_body(account: &Account, nonce: u64, call: &ContractCall) -> Result<(u64, String, serde_json::Value)> {
+    // The offset goes into the signed transaction itself, so the signature covers it.
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).ok()
+        .and_then(|d| u64::try_from(d.as_micros()).ok())
+        .and_then(|now| now.checked_add_signed(account.time_offset_micros))
+        .ok_or_else(|| OcsError::Signing("system clock is outside the u64 microsecond range".to_string()))?;
+
+    let tx = Transaction {
+        from: account.addr.clone(),
+        to_: call.contract.to_string(),
+        amount: call.amount.to_string(),
+        nonce,
+        ou: call.ou.to_string(),
+        timestamp,
+    };
+
+    let signature = sign_tx(&account.sk, &tx)?;
+    let blob = build_signing_blob(&tx);
+    verify_signature(&account.sk, blob.as_bytes(), &signature)?;
+    let pub_key = general_purpose::STANDARD.encode(account.sk.verifying_key().to_bytes());
+
+    let mut body = json!({
+        "contract": call.contract,
+        "method": call.method,
+        "params": call.params,
+        "caller": account.addr,
+        "nonce": nonce,
+        "ou": tx.ou,
+        "timestamp": tx.timestamp,
+        "signature": signature,
+        "public_key": pub_key
+    });
+    // Zero-value calls omit `amount`, which the node reads as "0".
+    if call.amount > 0 {
+        body["amount"] = json!(tx.amount);
+    }
+    Ok((timestamp, blob, body))
+}
+
+/// Updates the account's nonce counter from a submission result and builds the receipt.
+///
+/// A timed-out submission becomes [`OcsError::SubmitTimedOut`] and the nonce is re-read
+/// before the next transaction, since nobody knows whether the node used it.
+pub(crate) fn finish_tx(account: &mut Account, nonce: u64, timestamp: u64, res: Result<serde_json::Value>) -> Result<TxReceipt> {
+    let res = match res {
+        Ok(res) => res,
+        Err(OcsError::Http(source)) if source.is_timeout() => {
+            account.resync_nonce();
+            return Err(OcsError::SubmitTimedOut { nonce, source });
+        }
+        Err(e) => {
+            if matches!(&e, OcsError::Api(api) if api.is_nonce_error()) {
+                account.resync_nonce();
+            }
+            return Err(e);
+        }
+    };
+
+    // A 2xx answer without a hash means the node did not accept the transaction.
+    let Some(tx_hash) = res["tx_hash"].as_str().filter(|h| !h.is_empty()).map(str::to_string) else {
+        let detail = ["error", "message", "status"].iter()
+            .find_map(|key| res[key].as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| res.to_string());
+        if detail.to_lowercase().contains("nonce") {
+            account.resync_nonce();
+        }
+        return Err(OcsError::Rejected(format!("response has no tx_hash ({})", detail)));
+    };
+    account.commit_nonce(nonce);
+
+    Ok(TxReceipt { tx_hash, nonce, timestamp, raw: res })
+}
+
+// =============================
+// TX Simulation
+// =============================
+/// Signs `call` as [`try_send_tx`] would, but posts it to `POST /simulate-contract`, which
+/// executes it without committing anything. The nonce is not used up, so a real submission
+/// afterwards gets the same one.
+pub fn simulate_tx(rpc: &Rpc, account: &mut Account, call: &ContractCall) -> Result<Simulation> {
+    let nonce = account.next_nonce(rpc)?;
+    let (_, _, body) = call_body(account, nonce, call)?;
+    let res = api_call_with_timeout(rpc, "POST", "/simulate-contract", Some(body), call.timeout)?;
+    Simulation::from_raw(res)
+}
+
+// =============================
+// Audit Log
+// =============================
+/// Append-only JSONL record of the transactions submitted with [`TxOptions::audit`] set.
+///
+/// Each transaction gets a `signed` entry, written before it is submitted, holding the
+/// exact signing blob, the signature, the public key and the request body, which is enough
+/// to verify the signature independently. A `response` entry with the same signature then
+/// records the node's answer or the error. Every line carries `prev_sha256`, the SHA-256
+/// of the line before it, so an edited or removed entry breaks the chain. The private key
+/// is never written.
+pub struct AuditLog {
+    /// The open file and the hash of its last line.
+    inner: Mutex<(File, String)>,
+}
+
+impl AuditLog {
+    /// Opens `path` for appending, continuing the hash chain of the entries already in it.
+    pub fn open(path: &str) -> Result<Self> {
+        let prev = match std::fs::read_to_string(path) {
+            Ok(text) => text.lines().last().map(|line| format!("{:x}", Sha256::digest(line))).unwrap_or_default(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(OcsError::Audit(e)),
+        };
+        let file = OpenOptions::new().create(true).append(true).open(path).map_err(OcsError::Audit)?;
+        Ok(AuditLog { inner: Mutex::new((file, prev)) })
+    }
+
+    fn append(&self, mut entry: serde_json::Value) -> std::io::Result<()> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let (file, prev) = &mut *inner;
+        entry["timestamp"] = json!(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64());
+        entry["prev_sha256"] = json!(prev);
+        let line = entry.to_string();
+        writeln!(file, "{}", line)?;
+        file.sync_data()?;
+        *prev = format!("{:x}", Sha256::digest(&line));
+        Ok(())
+    }
+
+    /// Records a transaction about to be submitted. A failure here stops the submission.
+    pub(crate) fn record_signed(&self, blob: &str, body: &serde_json::Value) -> Result<()> {
+        self.append(json!({
+            "event": "signed",
+            "signing_blob": blob,
+            "signature": body["signature"],
+            "public_key": body["public_key"],
+            "request": body,
+        }))
+        .map_err(OcsError::Audit)
+    }
+
+    /// Records the node's answer to a submission. The transaction is already out by now,
+    /// so a write failure is only reported.
+    pub(crate) fn record_response(&self, body: &serde_json::Value, res: &Result<serde_json::Value>) {
+        let mut entry = json!({ "event": "response", "signature": body["signature"] });
+        match res {
+            Ok(response) => entry["response"] = response.clone(),
+            Err(e) => entry["error"] = json!(e.to_string()),
+        }
+        if let Err(e) = self.append(entry) {
+            eprintln!("⚠ Could not record the response in the audit file: {}", e);
+        }
+    }
+}
+
+// =============================
+// TX Confirmation
+// =============================
+/// Polls `GET /tx/{tx_hash}` every `poll_interval` until the node reports a
+/// status other than `pending`, or `timeout` elapses.
+///
+/// A 404 is treated as "not indexed yet" since the node may not know the hash right after
+/// submission. On timeout the last status seen is returned rather than an error.
+pub fn wait_for_confirmation(rpc: &Rpc, tx_hash: &str, timeout: Duration, poll_interval: Duration) -> Result<Confirmation> {
+    let path = format!("/tx/{}", tx_hash);
+    let deadline = Instant::now() + timeout;
+    let mut last = Confirmation { status: "not_found".to_string(), raw: serde_json::Value::Null };
+
+    loop {
+        let resp = send_request(rpc, "GET", &path, None)?;
+        if resp.status().as_u16() != 404 {
+            let (status, url, headers) = (resp.status().as_u16(), resp.url().to_string(), resp.headers().clone());
+            let text = resp.text()?;
+            log::debug!("response body: {}", text);
+            if status >= 400 {
+                return Err(ApiError::new(status, &url, text).with_retry_after(&headers).into());
+            }
+            let raw: serde_json::Value = serde_json::from_str(&text)?;
+            let status = raw["status"].as_str().unwrap_or("pending").to_string();
+            last = Confirmation { status, raw };
+            if last.status != "pending" {
+                return Ok(last);
+            }
+        }
+
+        if Instant::now() + poll_interval > deadline {
+            return Ok(last);
+        }
+        std::thread::sleep(poll_interval);
+    }
+}
+
+// =============================
+// Generate Params (random)
+// =============================
+const ALPHANUMERIC: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// What the placeholders in a param `example` stand for; see [`generate_params`].
+pub struct ParamContext<'a> {
+    /// Fills `{addr}`: the address of the wallet making the call.
+    pub addr: &'a str,
+    /// Fills `{contract}`: the contract the method is called on.
+    pub contract: &'a str,
+}
+
+impl ParamContext<'_> {
+    /// `example` with `{addr}`, `{contract}` and `{now}` (Unix seconds) filled in. Other
+    /// braces are left alone, so JSON examples pass through unchanged.
+    pub fn expand(&self, example: &str) -> String {
+        if !example.contains('{') {
+            return example.to_string();
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        example
+            .replace("{addr}", self.addr)
+            .replace("{contract}", self.contract)
+            .replace("{now}", &now.to_string())
+    }
+}
+
+/// Produces one value per param: the `example` if given, with its placeholders filled in
+/// from `ctx` (see [`ParamContext::expand`]), otherwise a random value for the param's
+/// `type`:
+///
+/// - `number`: an integer in `min..=max` (defaults 1 and 100)
+/// - `address`: a valid address for 32 random bytes, in the form [`derive_address`] produces
+/// - `bool`: `true` or `false`
+/// - `string`: `len` random alphanumeric characters (default 8)
+///
+/// Unknown types are treated as `number` with a warning on stderr. Pass a seeded RNG to
+/// get reproducible values.
+pub fn generate_params(params: &[Param], ctx: &ParamContext, rng: &mut impl Rng) -> Vec<String> {
+    params.iter().map(|p| {
+        if let Some(ex) = &p.example {
+            return ctx.expand(ex);
+        }
+        match p.param_type.as_str() {
+            "number" => random_number(rng, p),
+            "address" => {
+                let mut bytes = [0u8; 32];
+                rng.fill_bytes(&mut bytes);
+                format!("oct{}", bs58::encode(bytes).into_string())
+            }
+            "bool" => rng.gen_bool(0.5).to_string(),
+            "string" => random_chars(rng, ALPHANUMERIC, p.len.unwrap_or(8)),
+            other => {
+                eprintln!("⚠ Unknown param type '{}' for '{}', generating a number", other, p.name);
+                random_number(rng, p)
+            }
+        }
+    }).collect()
+}
+
+fn random_number(rng: &mut impl Rng, p: &Param) -> String {
+    rng.gen_range(p.min.unwrap_or(1)..=p.max.unwrap_or(100)).to_string()
+}
+
+fn random_chars(rng: &mut impl Rng, alphabet: &[u8], len: usize) -> String {
+    (0..len).map(|_| alphabet[rng.gen_range(0..alphabet.len())] as char).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    const ADDR: &str = "octJ8Uo9u28953Fpeeg7ki5H3cYhQ9V2w9Zotxr1nhFE2FJ";
+
+    fn key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn tx() -> Transaction {
+        Transaction {
+            from: ADDR.to_string(),
+            to_: ADDR.to_string(),
+            amount: "0".to_string(),
+            nonce: 5,
+            ou: "1".to_string(),
+            timestamp: 1_700_000_000_000_000,
+        }
+    }
+
+    #[test]
+    fn sign_tx_known_answer() {
+        assert_eq!(derive_address(&key().verifying_key()), ADDR);
+        assert_eq!(
+            sign_tx(&key(), &tx()).unwrap(),
+            "N9Asx+xQ9nKKkdQUaHQlz85mcJNOH3dp9DGS7LgMAJ0XhIiVqXfEV+SKrM37LdJXHDscSI/sKWuIO30ifcfCDg=="
+        );
+    }
+
+    #[test]
+    fn signature_round_trips() {
+        let sig = sign_tx(&key(), &tx()).unwrap();
+        verify_signature(&key(), build_signing_blob(&tx()).as_bytes(), &sig).unwrap();
+    }
+
+    #[test]
+    fn signing_blob_is_canonical() {
+        assert_eq!(BLOB_VERSION, 1);
+        assert_eq!(
+            build_signing_blob(&tx()),
+            r#"{"from":"octJ8Uo9u28953Fpeeg7ki5H3cYhQ9V2w9Zotxr1nhFE2FJ","to_":"octJ8Uo9u28953Fpeeg7ki5H3cYhQ9V2w9Zotxr1nhFE2FJ","amount":"0","nonce":5,"ou":"1","timestamp":1700000000000000}"#
+        );
+    }
+
+    #[test]
+    fn signing_blob_escapes_strings() {
+        let tx = Transaction {
+            from: "oct\"a\\b".to_string(),
+            to_: "oct\u{20ac}\n".to_string(),
+            amount: "0".to_string(),
+            nonce: 1,
+            ou: "1".to_string(),
+            timestamp: 2,
+        };
+        let blob = build_signing_blob(&tx);
+        assert_eq!(blob, r#"{"from":"oct\"a\\b","to_":"oct€\n","amount":"0","nonce":1,"ou":"1","timestamp":2}"#);
+        // Non-ASCII goes out as raw UTF-8, not as a \u escape.
+        assert!(blob.as_bytes().windows(3).any(|w| w == [0xe2, 0x82, 0xac]));
+
+        assert_eq!(
+            sign_tx(&key(), &tx).unwrap(),
+            "bOfz4VQmnJuaisx3PJfwn15SIBMM/14de3gp63/xYSMTWpl/XnL1UDyMYDxpbwQFuJ0gc4okeHPeBqCtdBM6Bw=="
+        );
+    }
+
+    #[test]
+    fn verify_signature_rejects_bad_input() {
+        let blob = build_signing_blob(&tx());
+        let sig = sign_tx(&key(), &tx()).unwrap();
+        verify_signature(&key(), blob.as_bytes(), &sig).unwrap();
+
+        let tampered = blob.replace("\"nonce\":5", "\"nonce\":6");
+        assert!(verify_signature(&key(), tampered.as_bytes(), &sig).is_err());
+
+        let other = SigningKey::from_bytes(&[8u8; 32]);
+        assert!(verify_signature(&other, blob.as_bytes(), &sig).is_err());
+
+        let err = verify_signature(&key(), blob.as_bytes(), "not base64!").unwrap_err();
+        assert!(err.to_string().contains("not base64"), "{}", err);
+    }
+
+    #[test]
+    fn call_body_matches_signed_blob() {
+        let account = Account::new(key(), ADDR.to_string());
+        let params = vec!["1".to_string()];
+        let call = ContractCall { contract: ADDR, method: "claim", params: &params, ou: 3, amount: 25, timeout: None };
+        let (timestamp, blob, body) = call_body(&account, 9, &call).unwrap();
+
+        let signed: serde_json::Value = serde_json::from_str(&blob).unwrap();
+        assert_eq!(body["timestamp"], json!(timestamp));
+        // The node rebuilds the blob from these body fields, so their JSON text must match.
+        for field in ["nonce", "ou", "timestamp", "amount"] {
+            let text = format!("\"{}\":{}", field, body[field]);
+            assert!(blob.contains(&text), "{} not in {}", text, blob);
+        }
+        assert_eq!(body["caller"], signed["from"]);
+        assert_eq!(body["contract"], signed["to_"]);
+        let sig = body["signature"].as_str().unwrap();
+        verify_signature(&key(), blob.as_bytes(), sig).unwrap();
+    }
+
+    #[test]
+    fn generated_addresses_are_valid() {
+        let param = Param {
+            name: "to".to_string(),
+            param_type: "address".to_string(),
+            example: None,
+            min: None,
+            max: None,
+            len: None,
+        };
+        let ctx = ParamContext { addr: ADDR, contract: ADDR };
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            let addr = generate_params(std::slice::from_ref(&param), &ctx, &mut rng).remove(0);
+            assert!(is_valid_address(&addr), "{}", addr);
+        }
+    }
+
+    #[test]
+    fn parse_micro_accepts_exact_amounts_only() {
+        assert_eq!(parse_micro("1_500_000").unwrap(), 1_500_000);
+        assert_eq!(parse_micro("1.5e6").unwrap(), 1_500_000);
+        assert_eq!(parse_micro("25000e-2").unwrap(), 250);
+        assert_eq!(parse_micro("0e-2147483648").unwrap(), 0);
+        for bad in ["1.5", "-1", "1e40", "1.5e-2147483648", "1e2147483647", "1ee2", ""] {
+            assert!(matches!(parse_micro(bad), Err(OcsError::BalanceParse(_))), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn balance_oct_uses_the_given_denomination() {
+        let balance = Balance { micro: 1_500_000, nonce: 0 };
+        assert_eq!(balance.oct(&Denomination::default()), 1.5);
+        assert_eq!(balance.oct(&Denomination::from_decimals(9).unwrap()), 0.0015);
+    }
+
+    #[test]
+    fn retry_after_is_capped_at_max_delay() {
+        let retry = RetryPolicy { max_delay: Duration::from_secs(5), ..RetryPolicy::default() };
+        let throttled = |secs| {
+            let mut api = ApiError::new(429, "http://node/call-contract", "{}".to_string());
+            api.retry_after = Some(Duration::from_secs(secs));
+            OcsError::Api(api)
+        };
+        assert_eq!(next_retry(&retry, 1, throttled(2)).unwrap(), Duration::from_secs(2));
+        assert_eq!(next_retry(&retry, 1, throttled(86_400)).unwrap(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn derive_ed25519_key_matches_slip10_test_vector_1() {
+        let seed: Vec<u8> = (0..16).collect();
+        let hex = |key: &[u8; 32]| key.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+        for (path, expected) in [
+            ("m", "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"),
+            ("m/0'", "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"),
+            ("m/0'/1'/2'/2'/1000000000'", "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793"),
+            ("m/0h/1h/2h/2h/1000000000h", "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793"),
+        ] {
+            assert_eq!(hex(&derive_ed25519_key(&seed, path).unwrap()), expected, "{}", path);
+        }
+        assert!(matches!(derive_ed25519_key(&seed, "m/0"), Err(OcsError::KeyDecode(_))));
+    }
+}
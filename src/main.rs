@@ -1,257 +1,2903 @@
-use serde::Deserialize;
-use serde_json::json;
 use std::{
+    collections::{BTreeMap, HashSet},
     fs,
-    io::Write,
-    time::{SystemTime, UNIX_EPOCH},
-    collections::HashMap
+    io::{IsTerminal, Read, Write},
+    net::{IpAddr, Ipv4Addr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{atomic::{AtomicBool, AtomicUsize, Ordering}, mpsc, Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
-use base64::{engine::general_purpose, Engine as _};
-use ed25519_dalek::{Signer, SigningKey};
+use ed25519_dalek::SigningKey;
 use reqwest::blocking::Client;
-use anyhow::{Result, bail};
-use rand::Rng;
+use anyhow::{Context, Result, bail};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use zeroize::Zeroizing;
+use base64::{engine::general_purpose, Engine as _};
+use ocs01_auto::{
+    batch_view_call, call_contract_tx, key_from_mnemonic, decode_private_key, decrypt_private_key, derive_address, generate_params, get_network, is_valid_address, get_balance, simulate_tx, view_call_with_timeout,
+    view_calls_parallel, wait_for_confirmation, with_retries, Account, AuditLog, Balance,
+    ContractCall, Denomination, Interface, Method, MethodType, NetworkInfo, ParamContext, OcsError, RetryPolicy, DEFAULT_DERIVATION_PATH, DEFAULT_OU, METHOD_TYPES, Rpc, Submission, TxOptions, TxReceipt, Wallet,
+};
+
+// =============================
+// Logging
+// =============================
+#[derive(Clone, Copy, PartialEq)]
+enum LogFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => bail!("unknown log format: {} (expected text or json)", s),
+        }
+    }
+}
+
+/// Format of the run's stdout.
+#[derive(Clone, Copy, PartialEq)]
+enum Output {
+    Text,
+    Json,
+}
+
+impl FromStr for Output {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Output::Text),
+            "json" => Ok(Output::Json),
+            _ => bail!("unknown output format: {} (expected text or json)", s),
+        }
+    }
+}
+
+/// `println!` for human-readable progress; under `--output json` it goes to stderr so
+/// stdout carries only the final JSON document.
+macro_rules! say {
+    ($args:expr, $($arg:tt)*) => {
+        if $args.output == Output::Json {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+/// What a report line carries besides its status.
+enum Detail<'a> {
+    Result(&'a serde_json::Value),
+    TxHash(&'a str),
+    /// A confirmation result, with the fee from the receipt when it has one.
+    Confirmed { tx_hash: &'a str, fee: Option<u128>, denom: Denomination },
+    /// A `--simulate` result.
+    Simulated { result: &'a serde_json::Value, gas_used: Option<u64> },
+    Error(&'a str),
+    None,
+}
+
+/// A view result for display: strings as they are, anything else as compact JSON.
+fn result_text(result: &serde_json::Value) -> String {
+    match result {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Writes a view result to `--result-dir` as `<method>.json`, pretty-printed, or as
+/// `<method>.txt` when it is a string that is not JSON. Repeated runs get `-<n>` and a
+/// `--wallets` run one directory per address. Failures only warn.
+fn save_result(args: &Args, addr: &str, method: &Method, iteration: Option<u32>, result: &serde_json::Value) {
+    let Some(dir) = &args.result_dir else { return };
+    let mut dir = PathBuf::from(dir);
+    if args.wallets.is_some() {
+        dir.push(addr);
+    }
+    let parsed = match result {
+        serde_json::Value::String(s) => serde_json::from_str(s).ok(),
+        other => Some(other.clone()),
+    };
+    let (ext, contents) = match &parsed {
+        Some(value) => ("json", serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())),
+        None => ("txt", result_text(result)),
+    };
+    let name: String = method.name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let path = match iteration {
+        Some(n) => dir.join(format!("{}-{}.{}", name, n, ext)),
+        None => dir.join(format!("{}.{}", name, ext)),
+    };
+    if let Err(e) = fs::create_dir_all(&dir).and_then(|_| fs::write(&path, contents + "\n")) {
+        eprintln!("⚠ Could not write {}: {}", path.display(), e);
+    }
+}
+
+/// `" (gas used N)"` for a simulation that reported its gas.
+fn gas_text(gas_used: Option<u64>) -> String {
+    gas_used.map(|gas| format!(" (gas used {})", gas)).unwrap_or_default()
+}
+
+/// The report file, opened once and shared by everything that logs during a run.
+struct Report {
+    /// `None` for a [`Report::section`], which buffers its lines in `buffered` instead.
+    file: Option<Mutex<fs::File>>,
+    buffered: Mutex<Vec<String>>,
+    path: String,
+    /// Roll the file over to [`rotated_path`] once it grows past this many bytes.
+    max_size: Option<u64>,
+    format: LogFormat,
+    dry_run: bool,
+    /// Latest status and detail of each method run so far, for `--output json`.
+    outcomes: Mutex<Vec<serde_json::Value>>,
+}
+
+impl Report {
+    fn open(path: &str, max_size: Option<u64>, format: LogFormat, dry_run: bool) -> Result<Self> {
+        Ok(Report {
+            file: Some(Mutex::new(open_append(path)?)),
+            buffered: Mutex::new(Vec::new()),
+            path: path.to_string(),
+            max_size,
+            format,
+            dry_run,
+            outcomes: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// A report for one wallet of a `--wallets` run. Its lines stay in memory until
+    /// [`Report::write_section`] appends them here in one piece, so concurrent wallets do
+    /// not interleave.
+    fn section(&self) -> Report {
+        Report {
+            file: None,
+            buffered: Mutex::new(Vec::new()),
+            path: self.path.clone(),
+            max_size: None,
+            format: self.format,
+            dry_run: self.dry_run,
+            outcomes: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Appends `section`'s lines under a header naming `wallet`, plus `error` if the
+    /// wallet's run failed.
+    fn write_section(&self, wallet: &str, section: Report, error: Option<&str>) -> Result<()> {
+        let mut lines = vec![match self.format {
+            LogFormat::Text => format!("===== Wallet {} =====", wallet),
+            LogFormat::Json => json!({ "wallet": wallet }).to_string(),
+        }];
+        lines.extend(section.buffered.into_inner().unwrap_or_else(|e| e.into_inner()));
+        if let Some(error) = error {
+            lines.push(match self.format {
+                LogFormat::Text => format!("Wallet {}: Error - {}", wallet, error),
+                LogFormat::Json => json!({ "wallet": wallet, "status": "error", "error": error }).to_string(),
+            });
+        }
+        self.write_lines(&lines)
+    }
+
+    /// Starts the outcome entry that following [`Report::log`] calls for `method` update.
+    /// `iteration` numbers the runs of a method with `repeat` set.
+    fn start(&self, method: &Method, iteration: Option<u32>) {
+        let mut outcome = json!({ "name": method.name, "type": method.method_type, "status": "pending" });
+        if let Some(contract) = &method.contract {
+            outcome["contract"] = json!(contract);
+        }
+        if let Some(n) = iteration {
+            outcome["iteration"] = json!(n);
+        }
+        self.outcomes.lock().unwrap_or_else(|e| e.into_inner()).push(outcome);
+    }
+
+    /// Records how long the current method's requests took; later log lines include it.
+    fn set_latency(&self, latency: Duration) {
+        if let Some(outcome) = self.outcomes.lock().unwrap_or_else(|e| e.into_inner()).last_mut() {
+            outcome["latency_ms"] = json!(latency.as_millis() as u64);
+        }
+    }
+
+    fn outcomes(&self) -> Vec<serde_json::Value> {
+        self.outcomes.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// The error, or else the status, logged for the method run last.
+    fn last_failure(&self) -> Option<String> {
+        let outcomes = self.outcomes.lock().unwrap_or_else(|e| e.into_inner());
+        let last = outcomes.last()?;
+        last["error"].as_str().or(last["status"].as_str()).map(str::to_string)
+    }
+
+    /// [`Report::outcomes`], leaving the list empty for the next `--every` run.
+    fn take_outcomes(&self) -> Vec<serde_json::Value> {
+        std::mem::take(&mut *self.outcomes.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    fn log(&self, method: &Method, status: &str, detail: Detail) -> Result<()> {
+        let (mut iteration, mut latency_ms) = (None, None);
+        if let Some(outcome) = self.outcomes.lock().unwrap_or_else(|e| e.into_inner()).last_mut()
+            && outcome["name"] == method.name.as_str()
+        {
+            outcome["status"] = json!(status);
+            match detail {
+                Detail::Result(r) => outcome["result"] = json!(r),
+                Detail::Simulated { result, gas_used } => {
+                    outcome["result"] = json!(result);
+                    outcome["gas_used"] = json!(gas_used);
+                }
+                Detail::TxHash(h) => outcome["tx_hash"] = json!(h),
+                Detail::Confirmed { tx_hash, fee, .. } => {
+                    outcome["tx_hash"] = json!(tx_hash);
+                    if let Some(fee) = fee {
+                        outcome["fee_micro"] = json!(fee.to_string());
+                    }
+                }
+                Detail::Error(e) => outcome["error"] = json!(e),
+                Detail::None => {}
+            }
+            iteration = outcome["iteration"].as_u64();
+            latency_ms = outcome["latency_ms"].as_u64();
+        }
+
+        let line = match self.format {
+            LogFormat::Text => {
+                let tag = if self.dry_run { "[DRY-RUN] " } else { "" };
+                let label = match iteration {
+                    Some(n) => format!("{} #{}", method.label, n),
+                    None => method.label.clone(),
+                };
+                let line = match detail {
+                    Detail::Result(r) => format!("{}{}: {}", tag, label, result_text(r)),
+                    Detail::Simulated { result, gas_used } => {
+                        format!("{}{}: {} - {}{}", tag, label, status, result_text(result), gas_text(gas_used))
+                    }
+                    Detail::TxHash(h) if status == "submitted" => format!("{}{}: TX Hash {}", tag, label, h),
+                    Detail::TxHash(h) => format!("{}{}: {} - TX Hash {}", tag, label, status, h),
+                    Detail::Confirmed { tx_hash, fee: Some(fee), denom } => {
+                        format!("{}{}: {} - TX Hash {} (fee {:.6} OCT)", tag, label, status, tx_hash, denom.to_oct(fee))
+                    }
+                    Detail::Confirmed { tx_hash, fee: None, .. } => format!("{}{}: {} - TX Hash {}", tag, label, status, tx_hash),
+                    Detail::Error(e) => format!("{}{}: Error - {}", tag, label, e),
+                    Detail::None => format!("{}{}: {}", tag, label, status),
+                };
+                match latency_ms {
+                    Some(ms) => format!("{} ({} ms)", line, ms),
+                    None => line,
+                }
+            }
+            LogFormat::Json => {
+                let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs_f64();
+                let mut entry = json!({
+                    "timestamp": timestamp,
+                    "method": method.name,
+                    "type": method.method_type,
+                    "status": status,
+                });
+                match detail {
+                    Detail::Result(r) => entry["result"] = json!(r),
+                    Detail::Simulated { result, gas_used } => {
+                        entry["result"] = json!(result);
+                        entry["gas_used"] = json!(gas_used);
+                    }
+                    Detail::TxHash(h) => entry["tx_hash"] = json!(h),
+                    Detail::Confirmed { tx_hash, fee, .. } => {
+                        entry["tx_hash"] = json!(tx_hash);
+                        if let Some(fee) = fee {
+                            entry["fee_micro"] = json!(fee.to_string());
+                        }
+                    }
+                    Detail::Error(e) => entry["error"] = json!(e),
+                    Detail::None => {}
+                }
+                if let Some(n) = iteration {
+                    entry["iteration"] = json!(n);
+                }
+                if let Some(ms) = latency_ms {
+                    entry["latency_ms"] = json!(ms);
+                }
+                if let Some(contract) = &method.contract {
+                    entry["contract"] = json!(contract);
+                }
+                if self.dry_run {
+                    entry["dry_run"] = json!(true);
+                }
+                entry.to_string()
+            }
+        };
+        self.write_line(&line)
+    }
+
+    fn log_summary(&self, summary: &Summary) -> Result<()> {
+        let line = match self.format {
+            LogFormat::Text => summary.to_string(),
+            LogFormat::Json => json!({
+                "timestamp": SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs_f64(),
+                "summary": summary.to_json(),
+            })
+            .to_string(),
+        };
+        self.write_line(&line)
+    }
+
+    /// Forces everything written so far to disk.
+    fn flush(&self) -> Result<()> {
+        let Some(file) = &self.file else { return Ok(()) };
+        let file = file.lock().map_err(|_| anyhow::anyhow!("report file lock poisoned"))?;
+        file.sync_data().context("failed to flush report file")
+    }
+
+    fn write_line(&self, line: &str) -> Result<()> {
+        self.write_lines(&[line.to_string()])
+    }
+
+    fn write_lines(&self, lines: &[String]) -> Result<()> {
+        let Some(file) = &self.file else {
+            self.buffered.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(lines);
+            return Ok(());
+        };
+        let mut file = file.lock().map_err(|_| anyhow::anyhow!("report file lock poisoned"))?;
+        for line in lines {
+            writeln!(file, "{}", line)?;
+        }
+
+        if let Some(max_size) = self.max_size
+            && file.metadata()?.len() > max_size
+        {
+            let rotated = rotated_path(&self.path);
+            fs::rename(&self.path, &rotated)
+                .with_context(|| format!("failed to rotate report file to {}", rotated))?;
+            *file = open_append(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+fn open_append(path: &str) -> Result<fs::File> {
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open report file {}", path))
+}
+
+/// `ocs01_report.txt` → `ocs01_report.1.txt`; the previous rotated file is replaced.
+fn rotated_path(path: &str) -> String {
+    let path = std::path::Path::new(path);
+    let stem = path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{}.1.{}", stem, ext.to_string_lossy()),
+        None => format!("{}.1", stem),
+    };
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
+
+/// A byte count written as a plain number or with a `K`, `M` or `G` suffix (powers of 1024).
+struct ByteSize(u64);
+
+impl FromStr for ByteSize {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (digits, unit) = match s.char_indices().last() {
+            Some((i, c)) if c.is_ascii_alphabetic() => (&s[..i], c.to_ascii_uppercase()),
+            _ => (s, 'B'),
+        };
+        let shift = match unit {
+            'B' => 0,
+            'K' => 10,
+            'M' => 20,
+            'G' => 30,
+            _ => bail!("unknown size unit in {} (expected K, M or G)", s),
+        };
+        let n: u64 = digits.trim().parse().with_context(|| format!("invalid size {}", s))?;
+        n.checked_mul(1 << shift).map(ByteSize).with_context(|| format!("size {} is too large", s))
+    }
+}
+
+/// A time span written as plain seconds or with an `ms`, `s`, `m` or `h` suffix.
+#[derive(Clone, Copy)]
+struct Interval(Duration);
+
+impl FromStr for Interval {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (digits, unit) = s.split_at(split);
+        let n: u64 = digits.parse().with_context(|| format!("invalid duration {}", s))?;
+        let duration = match unit.trim() {
+            "ms" => Duration::from_millis(n),
+            "" | "s" => Duration::from_secs(n),
+            "m" => Duration::from_secs(n * 60),
+            "h" => Duration::from_secs(n * 3600),
+            _ => bail!("unknown duration unit in {} (expected ms, s, m or h)", s),
+        };
+        Ok(Interval(duration))
+    }
+}
+
+/// Per-run tally of method outcomes.
+struct Summary {
+    total: usize,
+    succeeded: usize,
+    /// Names of the methods that failed, in run order.
+    failed: Vec<String>,
+    /// Names of the methods that were not run, e.g. calls declined under `--confirm`.
+    skipped: Vec<String>,
+    /// Nonce of the last transaction signed during the run.
+    last_nonce: Option<u64>,
+    /// Fees reported by confirmed receipts, in raw units, and how many receipts had one.
+    fees_micro: u128,
+    fees_reported: usize,
+    /// The wallet's token precision, for showing fees in OCT.
+    denom: Denomination,
+    /// Request time of every method run that was timed on its own (not prefetched).
+    latencies: Vec<Duration>,
+    started: Instant,
+}
+
+impl Summary {
+    fn new(denom: Denomination) -> Self {
+        Summary { total: 0, succeeded: 0, failed: Vec::new(), skipped: Vec::new(), last_nonce: None, fees_micro: 0, fees_reported: 0, denom, latencies: Vec::new(), started: Instant::now() }
+    }
+
+    fn record(&mut self, method: &Method, ok: bool) {
+        self.total += 1;
+        if ok {
+            self.succeeded += 1;
+        } else {
+            self.failed.push(method.name.clone());
+        }
+    }
+
+    fn skip(&mut self, method: &Method) {
+        self.skipped.push(method.name.clone());
+    }
+
+    fn record_fee(&mut self, fee: Option<u128>) {
+        if let Some(fee) = fee {
+            self.fees_micro += fee;
+            self.fees_reported += 1;
+        }
+    }
+
+    /// Min, average and max of [`Summary::latencies`] in milliseconds.
+    fn latency_stats(&self) -> Option<(u128, u128, u128)> {
+        let ms: Vec<u128> = self.latencies.iter().map(Duration::as_millis).collect();
+        let (min, max) = (*ms.iter().min()?, *ms.iter().max()?);
+        Some((min, ms.iter().sum::<u128>() / ms.len() as u128, max))
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let latency = self.latency_stats().map(|(min, avg, max)| json!({ "min": min as u64, "avg": avg as u64, "max": max as u64 }));
+        json!({
+            "total": self.total,
+            "succeeded": self.succeeded,
+            "failed": self.failed,
+            "skipped": self.skipped,
+            "last_nonce": self.last_nonce,
+            "fees_micro": self.fees_micro.to_string(),
+            "fees_reported": self.fees_reported,
+            "latency_ms": latency,
+            "elapsed_secs": self.started.elapsed().as_secs_f64(),
+        })
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Summary: {} methods, {} succeeded, {} failed in {:.1}s",
+            self.total, self.succeeded, self.failed.len(), self.started.elapsed().as_secs_f64()
+        )?;
+        if !self.failed.is_empty() {
+            write!(f, " (failed: {})", self.failed.join(", "))?;
+        }
+        if !self.skipped.is_empty() {
+            write!(f, ", {} skipped ({})", self.skipped.len(), self.skipped.join(", "))?;
+        }
+        if let Some(nonce) = self.last_nonce {
+            write!(f, ", last nonce {}", nonce)?;
+        }
+        if let Some((min, avg, max)) = self.latency_stats() {
+            write!(f, ", latency min/avg/max {}/{}/{} ms", min, avg, max)?;
+        }
+        if self.fees_reported > 0 {
+            write!(f, ", fees {:.6} OCT over {} receipts", self.denom.to_oct(self.fees_micro), self.fees_reported)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes this crate's `log` records to stderr.
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target().starts_with("ocs01_auto") && metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn init_logging(verbosity: u8) -> Result<()> {
+    let level = match verbosity {
+        0 => log::LevelFilter::Warn,
+        1 => log::LevelFilter::Info,
+        _ => log::LevelFilter::Debug,
+    };
+    log::set_logger(&StderrLogger).map_err(|e| anyhow::anyhow!("failed to set logger: {}", e))?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+// =============================
+// Interrupt Handling
+// =============================
+/// Exit code of a run stopped with Ctrl-C (128 + SIGINT).
+const EXIT_INTERRUPTED: i32 = 130;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// The first Ctrl-C lets the current method finish, so no transaction is left in an unknown
+/// state; a second one exits immediately.
+fn install_interrupt_handler() -> Result<()> {
+    ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            std::process::exit(EXIT_INTERRUPTED);
+        }
+        eprintln!("\n⏹ Interrupted: stopping after the current method (press Ctrl-C again to quit now)");
+    })
+    .context("failed to install the Ctrl-C handler")
+}
+
+fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+// =============================
+// Metrics
+// =============================
+/// Upper bounds, in seconds, of the request latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 8] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Totals for one wallet since the process started, for `--metrics-file` and
+/// `--metrics-port`.
+#[derive(Default)]
+struct WalletMetrics {
+    runs: u64,
+    runs_failed: u64,
+    methods: u64,
+    succeeded: u64,
+    failed: u64,
+    /// Balance in OCT at the start of the last run that got that far.
+    balance: Option<f64>,
+    /// Cumulative count of timed methods per [`LATENCY_BUCKETS`] bound.
+    latency_buckets: [u64; LATENCY_BUCKETS.len()],
+    latency_sum: f64,
+    latency_count: u64,
+    last_run: f64,
+}
+
+static METRICS: Mutex<BTreeMap<String, WalletMetrics>> = Mutex::new(BTreeMap::new());
+
+/// Adds a run of `wallet` to the metrics, `None` meaning it failed before running any
+/// method, and rewrites `--metrics-file` if set.
+fn record_metrics(args: &Args, wallet: &str, run: Option<&WalletRun>) -> Result<()> {
+    if args.metrics_file.is_none() && args.metrics_port.is_none() {
+        return Ok(());
+    }
+    let text = {
+        let mut metrics = METRICS.lock().unwrap_or_else(|e| e.into_inner());
+        let m = metrics.entry(wallet.to_string()).or_default();
+        m.runs += 1;
+        m.last_run = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs_f64();
+        match run {
+            Some(run) => {
+                let summary = &run.summary;
+                if !summary.failed.is_empty() || run.stop.is_some() {
+                    m.runs_failed += 1;
+                }
+                m.methods += summary.total as u64;
+                m.succeeded += summary.succeeded as u64;
+                m.failed += summary.failed.len() as u64;
+                if let Some(balance) = &run.balance {
+                    m.balance = Some(summary.denom.to_oct(balance.micro));
+                }
+                for latency in &summary.latencies {
+                    let secs = latency.as_secs_f64();
+                    for (count, bound) in m.latency_buckets.iter_mut().zip(LATENCY_BUCKETS) {
+                        if secs <= bound {
+                            *count += 1;
+                        }
+                    }
+                    m.latency_sum += secs;
+                    m.latency_count += 1;
+                }
+            }
+            None => m.runs_failed += 1,
+        }
+        render_metrics(&metrics)
+    };
+    if let Some(path) = &args.metrics_file {
+        // Written aside and renamed, so a scraper never reads half a file.
+        let tmp = format!("{}.tmp", path);
+        fs::write(&tmp, text)
+            .and_then(|()| fs::rename(&tmp, path))
+            .with_context(|| format!("failed to write metrics file {}", path))?;
+    }
+    Ok(())
+}
+
+/// The metrics in the Prometheus text exposition format, labelled by wallet address.
+fn render_metrics(metrics: &BTreeMap<String, WalletMetrics>) -> String {
+    use std::fmt::Write as _;
+
+    type Value = fn(&WalletMetrics) -> Option<f64>;
+    let families: [(&str, &str, &str, Value); 7] = [
+        ("ocs01_runs_total", "counter", "Runs finished, whether they succeeded or not.", |m| Some(m.runs as f64)),
+        ("ocs01_runs_failed_total", "counter", "Runs that had a failed method, stopped early or failed to start.", |m| Some(m.runs_failed as f64)),
+        ("ocs01_methods_total", "counter", "Method runs, including repeats.", |m| Some(m.methods as f64)),
+        ("ocs01_methods_succeeded_total", "counter", "Method runs that succeeded.", |m| Some(m.succeeded as f64)),
+        ("ocs01_methods_failed_total", "counter", "Method runs that failed.", |m| Some(m.failed as f64)),
+        ("ocs01_balance_oct", "gauge", "Wallet balance at the start of the last run.", |m| m.balance),
+        ("ocs01_last_run_timestamp_seconds", "gauge", "Unix time the last run ended.", |m| Some(m.last_run)),
+    ];
+    let mut out = String::new();
+    for (name, kind, help, value) in families {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+        for (wallet, m) in metrics {
+            if let Some(v) = value(m) {
+                let _ = writeln!(out, "{}{{wallet=\"{}\"}} {}", name, wallet, v);
+            }
+        }
+    }
+    let name = "ocs01_request_duration_seconds";
+    let _ = writeln!(out, "# HELP {} Time taken by the requests of each timed method run.\n# TYPE {} histogram", name, name);
+    for (wallet, m) in metrics {
+        for (bound, count) in LATENCY_BUCKETS.iter().zip(m.latency_buckets) {
+            let _ = writeln!(out, "{}_bucket{{wallet=\"{}\",le=\"{}\"}} {}", name, wallet, bound, count);
+        }
+        let _ = writeln!(out, "{}_bucket{{wallet=\"{}\",le=\"+Inf\"}} {}", name, wallet, m.latency_count);
+        let _ = writeln!(out, "{}_sum{{wallet=\"{}\"}} {}", name, wallet, m.latency_sum);
+        let _ = writeln!(out, "{}_count{{wallet=\"{}\"}} {}", name, wallet, m.latency_count);
+    }
+    out
+}
+
+/// Serves the metrics at any path on `--metrics-bind`:`--metrics-port` from a background
+/// thread, for as long as the process runs.
+fn serve_metrics(args: &Args) -> Result<()> {
+    let Some(port) = args.metrics_port else { return Ok(()) };
+    let listener = TcpListener::bind((args.metrics_bind, port))
+        .with_context(|| format!("cannot listen on {}:{} (--metrics-bind, --metrics-port)", args.metrics_bind, port))?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(e) = respond_metrics(stream) {
+                log::debug!("metrics request failed: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+fn respond_metrics(mut stream: TcpStream) -> std::io::Result<()> {
+    // Only the start of the request is read; every request gets the metrics.
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let _ = stream.read(&mut [0; 1024])?;
+    let body = render_metrics(&METRICS.lock().unwrap_or_else(|e| e.into_inner()));
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(), body
+    )
+}
+
+// =============================
+// CLI Arguments
+// =============================
+const USAGE: &str = "\
+Usage: ocs01-auto [OPTIONS] [COMMAND]
+
+Commands:
+  run                  run every interface method (default)
+  balance              print the wallet balance and nonce
+  methods              list the interface methods and their params, offline
+                       (also --list-methods)
+  whoami               print the wallet key's public key and address, offline
+  view <method> [params...]
+                       call one view method with the given params
+  call <method> [params...]
+                       submit one call transaction with the given params
+
+Options:
+  --wallet <path>      wallet file (default: wallet.json), or - for stdin
+  --wallets <path>     JSON array of wallets to run the interface for, instead of --wallet
+  --wallet-concurrency <n> run up to n wallets of --wallets at once (default: 1)
+  --every <duration>   run again this long after each run ends, e.g. 30s, 10m or 1h
+  --count <n>          stop after n runs of --every (default: no limit)
+  --metrics-file <path> write Prometheus metrics to this file after every run
+  --metrics-port <port> serve Prometheus metrics over HTTP while running
+  --metrics-bind <addr> address to serve the metrics on (default: 127.0.0.1)
+  --interface <path>   interface file or directory of them (default: exec_interface.json),
+                       or - for stdin
+  --contract <addr>    contract address, instead of the one in the interface file
+  --as <addr>          make view calls as this address instead of the wallet's
+  --dry-run            sign transactions but print them instead of submitting
+  --simulate           run call methods on the node's /simulate-contract, committing nothing
+  --timeout <s>        HTTP request timeout in seconds (default: 100)
+  --connect-timeout <s> HTTP connect timeout in seconds (default: 10)
+  --pool-max-idle <n>  idle connections kept per node for reuse; 0 disables (default: 8)
+  --pool-idle-timeout <s> close pooled connections idle this long (default: 90)
+  --tcp-keepalive <s>  TCP keep-alive probe interval; 0 disables (default: 60)
+  --rps <n>            limit requests to the node to n per second
+  --proxy <url>        HTTP(S) or SOCKS5 proxy (default: HTTPS_PROXY / HTTP_PROXY)
+  --tls-cert <path>    PEM client certificate for mutual TLS (needs --tls-key)
+  --tls-key <path>     PEM (PKCS#8) key for --tls-cert
+  --tls-ca <path>      extra PEM root CA to trust, e.g. for a self-signed node
+  --user-agent <ua>    User-Agent header (default: ocs01-auto/<version>)
+  --no-compression     do not ask the node for gzip, deflate or brotli responses
+  --header <name: value> extra HTTP header for every request (repeatable)
+  --no-addr-check      warn instead of failing when addr does not match the key
+  --wait               wait for each transaction to be confirmed
+  --confirm            ask before submitting each call transaction (y, n, or a for all)
+  --confirm-no-tty <error|decline>
+                       what --confirm does without a terminal on stdin (default: error)
+  --wait-timeout <s>   give up waiting after this many seconds (default: 60)
+  --poll-interval <s>  seconds between confirmation checks (default: 2)
+  --methods <a,b,...>  only run the named methods
+  --skip <a,b,...>     do not run the named methods
+  --only-views         run only the view methods
+  --only-calls         run only the call methods
+  --fee-per-call <oct> estimated fee per call at ou 1, for the balance check (default: 0.001)
+  --no-initial-balance skip the balance check at the start of a run
+  --low-balance <oct>  warn at startup when the balance is below this (default: 0.01)
+  --max-gas <oct>      stop the run before estimated call fees would exceed this
+  --time-offset <secs> shift transaction timestamps by secs (negative if the local
+                       clock is ahead of the node's)
+  --min-nonce <n>      refuse to sign any transaction with a nonce below n
+  --audit-file <path>  append each signed transaction and the node's answer as
+                       hash-chained JSONL
+  --continue-on-nonce-error
+                       on a nonce conflict, re-read the nonce from the node and try again
+  --nonce-resyncs <n>  how often --continue-on-nonce-error may do so per call (default: 2)
+  --seed <u64>         seed for generated params, to replay a run
+  --shuffle            run the methods in random order (seeded), views and calls each among themselves
+  --param <name=value> use this value for every param with that name (repeatable)
+  --params-file <path> JSON object of method name to exact params, used instead of generating
+  --batch-views        send all view methods in one batch request before the calls
+  --concurrency <n>    run view methods on up to n threads before the calls (default: 1)
+  --delay <ms>         pause between methods in milliseconds (default: 2000)
+  --ignore-errors      exit successfully even if some methods failed
+  --strict             fail instead of warning when there are no methods to run
+  --fail-fast          stop the run at the first failed method
+  --state-file <path>  remember the last submitted nonce per address between runs
+  --checkpoint <path>  file recording the calls each run has submitted
+                       (default: ocs01_checkpoint.json)
+  --resume             skip the calls an interrupted run already submitted
+  --report <path>      report file (default: ocs01_report.txt)
+  --result-dir <path>  also write each view result to its own file in this directory
+  --report-max-size <n> rotate the report to <name>.1.<ext> past n bytes (K/M/G suffixes allowed)
+  --log-format <fmt>   report format: text (default) or json (one object per line)
+  --output <fmt>       stdout format: text (default) or json (one document at the end)
+  -i, --interactive    read methods and params from a prompt instead of running them all
+  -q, --quiet          do not show run progress on stderr
+  -v, --verbose        trace requests to stderr; -vv also logs bodies
+  --config <path>      defaults file to read (default: ./ocs01.toml, then
+                       ~/.config/ocs01/ocs01.toml); options given here replace
+                       its values, and --no-<switch> turns off one of its switches
+  --no-config          ignore the defaults file
+  -h, --help           print this help";
+
+enum Command {
+    Run,
+    Balance,
+    Methods,
+    WhoAmI,
+    View { method: String, params: Vec<String> },
+    Call { method: String, params: Vec<String> },
+}
+
+struct Args {
+    command: Command,
+    wallet: String,
+    interface: String,
+    contract: Option<String>,
+    /// `--as`: the address view calls are made as.
+    view_as: Option<String>,
+    dry_run: bool,
+    timeout: Option<u64>,
+    connect_timeout: Option<u64>,
+    pool_max_idle: Option<usize>,
+    pool_idle_timeout: Option<u64>,
+    tcp_keepalive: Option<u64>,
+    rps: Option<f64>,
+    proxy: Option<String>,
+    tls_cert: Option<String>,
+    tls_key: Option<String>,
+    tls_ca: Option<String>,
+    user_agent: Option<String>,
+    headers: Vec<(String, String)>,
+    no_addr_check: bool,
+    no_compression: bool,
+    simulate: bool,
+    wait: bool,
+    confirm: bool,
+    /// `--confirm-no-tty decline`: without a terminal, decline every call instead of failing.
+    decline_without_tty: bool,
+    wait_timeout: u64,
+    poll_interval: u64,
+    methods: Vec<String>,
+    skip: Vec<String>,
+    /// `--only-views` or `--only-calls`.
+    only_type: Option<MethodType>,
+    fee_per_call: f64,
+    low_balance: f64,
+    no_initial_balance: bool,
+    max_gas: Option<f64>,
+    min_nonce: Option<u64>,
+    time_offset: Option<f64>,
+    audit_file: Option<String>,
+    continue_on_nonce_error: bool,
+    nonce_resyncs: u32,
+    seed: Option<u64>,
+    shuffle: bool,
+    param_overrides: Vec<(String, String)>,
+    params_file: Option<String>,
+    batch_views: bool,
+    concurrency: usize,
+    wallets: Option<String>,
+    wallet_concurrency: usize,
+    every: Option<Interval>,
+    count: Option<u32>,
+    metrics_file: Option<String>,
+    metrics_port: Option<u16>,
+    metrics_bind: IpAddr,
+    delay: u64,
+    ignore_errors: bool,
+    fail_fast: bool,
+    strict: bool,
+    state_file: Option<String>,
+    checkpoint: String,
+    resume: bool,
+    report: String,
+    result_dir: Option<String>,
+    report_max_size: Option<u64>,
+    log_format: LogFormat,
+    output: Output,
+    interactive: bool,
+    quiet: bool,
+    verbosity: u8,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut args = Args {
+        command: Command::Run,
+        wallet: "wallet.json".to_string(),
+        interface: "exec_interface.json".to_string(),
+        contract: None,
+        view_as: None,
+        dry_run: false,
+        timeout: None,
+        connect_timeout: None,
+        pool_max_idle: None,
+        pool_idle_timeout: None,
+        tcp_keepalive: None,
+        rps: None,
+        proxy: None,
+        tls_cert: None,
+        tls_key: None,
+        tls_ca: None,
+        user_agent: None,
+        headers: Vec::new(),
+        no_addr_check: false,
+        no_compression: false,
+        simulate: false,
+        wait: false,
+        confirm: false,
+        decline_without_tty: false,
+        wait_timeout: 60,
+        poll_interval: 2,
+        methods: Vec::new(),
+        skip: Vec::new(),
+        only_type: None,
+        fee_per_call: 0.001,
+        low_balance: 0.01,
+        no_initial_balance: false,
+        max_gas: None,
+        min_nonce: None,
+        time_offset: None,
+        audit_file: None,
+        continue_on_nonce_error: false,
+        nonce_resyncs: 2,
+        seed: None,
+        shuffle: false,
+        param_overrides: Vec::new(),
+        params_file: None,
+        batch_views: false,
+        concurrency: 1,
+        wallets: None,
+        wallet_concurrency: 1,
+        every: None,
+        count: None,
+        metrics_file: None,
+        metrics_port: None,
+        metrics_bind: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        delay: 2000,
+        ignore_errors: false,
+        fail_fast: false,
+        strict: false,
+        state_file: None,
+        checkpoint: "ocs01_checkpoint.json".to_string(),
+        resume: false,
+        report: "ocs01_report.txt".to_string(),
+        result_dir: None,
+        report_max_size: None,
+        log_format: LogFormat::Text,
+        output: Output::Text,
+        interactive: false,
+        quiet: false,
+        verbosity: 0,
+    };
+
+    let mut positional = Vec::new();
+    let mut list_methods = false;
+    let cli: Vec<String> = std::env::args().skip(1).collect();
+    let defaults = match config_path(&cli)? {
+        Some(path) => config_args(&path)?,
+        None => Vec::new(),
+    };
+    let mut iter = merge_config(defaults, cli).into_iter();
+    while let Some(arg) = iter.next() {
+        if arg.starts_with("--") && option_takes_value(&arg).is_none() {
+            bail!("unknown argument: {}\n\n{}", arg, USAGE);
+        }
+        match arg.as_str() {
+            "--wallet" => args.wallet = flag_value(&mut iter, &arg)?,
+            "--interface" => args.interface = flag_value(&mut iter, &arg)?,
+            "--contract" => args.contract = Some(flag_value(&mut iter, &arg)?),
+            "--as" => args.view_as = Some(flag_value(&mut iter, &arg)?),
+            "--dry-run" => args.dry_run = true,
+            "--simulate" => args.simulate = true,
+            "--timeout" => args.timeout = Some(flag_parse(&mut iter, &arg)?),
+            "--connect-timeout" => args.connect_timeout = Some(flag_parse(&mut iter, &arg)?),
+            "--pool-max-idle" => args.pool_max_idle = Some(flag_parse(&mut iter, &arg)?),
+            "--pool-idle-timeout" => args.pool_idle_timeout = Some(flag_parse(&mut iter, &arg)?),
+            "--tcp-keepalive" => args.tcp_keepalive = Some(flag_parse(&mut iter, &arg)?),
+            "--rps" => args.rps = Some(flag_parse(&mut iter, &arg)?),
+            "--proxy" => args.proxy = Some(flag_value(&mut iter, &arg)?),
+            "--tls-cert" => args.tls_cert = Some(flag_value(&mut iter, &arg)?),
+            "--tls-key" => args.tls_key = Some(flag_value(&mut iter, &arg)?),
+            "--tls-ca" => args.tls_ca = Some(flag_value(&mut iter, &arg)?),
+            "--user-agent" => args.user_agent = Some(flag_value(&mut iter, &arg)?),
+            "--header" => args.headers.push(flag_header(&mut iter, &arg)?),
+            "--no-addr-check" => args.no_addr_check = true,
+            "--no-compression" => args.no_compression = true,
+            "--wait" => args.wait = true,
+            "--confirm" => args.confirm = true,
+            "--confirm-no-tty" => {
+                args.decline_without_tty = match flag_value(&mut iter, &arg)?.as_str() {
+                    "error" => false,
+                    "decline" => true,
+                    other => bail!("invalid value for --confirm-no-tty: {} (expected error or decline)", other),
+                }
+            }
+            "--wait-timeout" => args.wait_timeout = flag_parse(&mut iter, &arg)?,
+            "--poll-interval" => args.poll_interval = flag_parse(&mut iter, &arg)?,
+            "--methods" => args.methods.extend(flag_list(&mut iter, &arg)?),
+            "--skip" => args.skip.extend(flag_list(&mut iter, &arg)?),
+            "--only-views" | "--only-calls" => {
+                let only = if arg == "--only-views" { MethodType::View } else { MethodType::Call };
+                if args.only_type.is_some_and(|t| t != only) {
+                    bail!("--only-views and --only-calls cannot be combined");
+                }
+                args.only_type = Some(only);
+            }
+            "--fee-per-call" => args.fee_per_call = flag_parse(&mut iter, &arg)?,
+            "--low-balance" => args.low_balance = flag_parse(&mut iter, &arg)?,
+            "--no-initial-balance" => args.no_initial_balance = true,
+            "--max-gas" => args.max_gas = Some(flag_parse(&mut iter, &arg)?),
+            "--min-nonce" => args.min_nonce = Some(flag_parse(&mut iter, &arg)?),
+            "--time-offset" => args.time_offset = Some(flag_parse(&mut iter, &arg)?),
+            "--audit-file" => args.audit_file = Some(flag_value(&mut iter, &arg)?),
+            "--continue-on-nonce-error" => args.continue_on_nonce_error = true,
+            "--nonce-resyncs" => args.nonce_resyncs = flag_parse(&mut iter, &arg)?,
+            "--seed" => args.seed = Some(flag_parse(&mut iter, &arg)?),
+            "--shuffle" => args.shuffle = true,
+            "--param" => args.param_overrides.push(flag_param(&mut iter, &arg)?),
+            "--params-file" => args.params_file = Some(flag_value(&mut iter, &arg)?),
+            "--batch-views" => args.batch_views = true,
+            "--concurrency" => args.concurrency = flag_parse(&mut iter, &arg)?,
+            "--wallets" => args.wallets = Some(flag_value(&mut iter, &arg)?),
+            "--wallet-concurrency" => args.wallet_concurrency = flag_parse(&mut iter, &arg)?,
+            "--every" => args.every = Some(flag_parse(&mut iter, &arg)?),
+            "--count" => args.count = Some(flag_parse(&mut iter, &arg)?),
+            "--metrics-file" => args.metrics_file = Some(flag_value(&mut iter, &arg)?),
+            "--metrics-port" => args.metrics_port = Some(flag_parse(&mut iter, &arg)?),
+            "--metrics-bind" => args.metrics_bind = flag_parse(&mut iter, &arg)?,
+            "--delay" => args.delay = flag_parse(&mut iter, &arg)?,
+            "--ignore-errors" => args.ignore_errors = true,
+            "--strict" => args.strict = true,
+            "--fail-fast" => args.fail_fast = true,
+            "--state-file" => args.state_file = Some(flag_value(&mut iter, &arg)?),
+            "--checkpoint" => args.checkpoint = flag_value(&mut iter, &arg)?,
+            "--resume" => args.resume = true,
+            "--report" => args.report = flag_value(&mut iter, &arg)?,
+            "--result-dir" => args.result_dir = Some(flag_value(&mut iter, &arg)?),
+            "--report-max-size" => args.report_max_size = Some(flag_parse::<ByteSize>(&mut iter, &arg)?.0),
+            "--log-format" => args.log_format = flag_value(&mut iter, &arg)?.parse()?,
+            "--output" => args.output = flag_value(&mut iter, &arg)?.parse()?,
+            "-i" | "--interactive" => args.interactive = true,
+            "--list-methods" => list_methods = true,
+            "-q" | "--quiet" => args.quiet = true,
+            "-v" | "--verbose" => args.verbosity += 1,
+            "-vv" => args.verbosity += 2,
+            "--config" => {
+                flag_value(&mut iter, &arg)?;
+            }
+            "--no-config" => {}
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            // Single-dash values such as "-5" are kept as positional params.
+            _ if !arg.starts_with("--") => positional.push(arg),
+            _ => bail!("unknown argument: {}\n\n{}", arg, USAGE),
+        }
+    }
+
+    let stdin_inputs: Vec<&str> = [
+        ("--wallet", Some(args.wallet.as_str()).filter(|_| args.wallets.is_none())),
+        ("--interface", Some(args.interface.as_str())),
+        ("--wallets", args.wallets.as_deref()),
+        ("--params-file", args.params_file.as_deref()),
+    ]
+    .into_iter()
+    .filter(|(_, path)| *path == Some("-"))
+    .map(|(flag, _)| flag)
+    .collect();
+    if stdin_inputs.len() > 1 {
+        bail!("only one input can be read from stdin, got - for {}", stdin_inputs.join(" and "));
+    }
+    if !stdin_inputs.is_empty() && args.interactive {
+        bail!("{} - cannot be combined with --interactive, which reads commands from stdin", stdin_inputs[0]);
+    }
+    if args.confirm && args.wallet_concurrency > 1 {
+        bail!("--confirm cannot be combined with --wallet-concurrency above 1");
+    }
+    if args.simulate && args.dry_run {
+        bail!("--simulate and --dry-run cannot be combined");
+    }
+    if args.fail_fast && args.ignore_errors {
+        bail!("--fail-fast and --ignore-errors cannot be combined");
+    }
+    if args.count.is_some() && args.every.is_none() {
+        bail!("--count needs --every");
+    }
+    if args.time_offset.is_some_and(|secs| !secs.is_finite() || secs.abs() > 86_400.0) {
+        bail!("--time-offset must be at most a day either way");
+    }
+    if args.count == Some(0) {
+        bail!("--count must be at least 1");
+    }
+    if args.every.is_some() && (args.wallets.is_some() || args.interactive) {
+        bail!("--every cannot be combined with --wallets or --interactive");
+    }
+
+    if let Some(contract) = &args.contract
+        && !is_valid_address(contract)
+    {
+        bail!("--contract {} is not a valid address (expected oct followed by base58)", contract);
+    }
+    if let Some(caller) = &args.view_as
+        && !is_valid_address(caller)
+    {
+        bail!("--as {} is not a valid address (expected oct followed by base58)", caller);
+    }
+
+    let mut positional = positional.into_iter();
+    args.command = match positional.next().as_deref() {
+        None if list_methods => Command::Methods,
+        None | Some("run") => Command::Run,
+        Some("balance") => Command::Balance,
+        Some("methods") => Command::Methods,
+        Some("whoami") => Command::WhoAmI,
+        Some("view") => match positional.next() {
+            Some(method) => Command::View { method, params: positional.by_ref().collect() },
+            None => bail!("view needs a method name\n\n{}", USAGE),
+        },
+        Some("call") => match positional.next() {
+            Some(method) => Command::Call { method, params: positional.by_ref().collect() },
+            None => bail!("call needs a method name\n\n{}", USAGE),
+        },
+        Some(other) => bail!("unknown command: {}\n\n{}", other, USAGE),
+    };
+    if let Some(extra) = positional.next() {
+        bail!("unexpected argument: {}\n\n{}", extra, USAGE);
+    }
+    // Only commands that submit calls ever prompt.
+    let submits_calls = matches!(args.command, Command::Run | Command::Call { .. });
+    if submits_calls && args.confirm && !args.decline_without_tty && !std::io::stdin().is_terminal() {
+        bail!("--confirm needs a terminal on stdin; pass --confirm-no-tty decline to skip every call instead");
+    }
+    Ok(args)
+}
+
+fn flag_value(iter: &mut impl Iterator<Item = String>, flag: &str) -> Result<String> {
+    match iter.next() {
+        Some(v) => Ok(v),
+        None => bail!("missing value for {}", flag),
+    }
+}
+
+fn flag_parse<T: FromStr>(iter: &mut impl Iterator<Item = String>, flag: &str) -> Result<T> {
+    let value = flag_value(iter, flag)?;
+    match value.parse() {
+        Ok(v) => Ok(v),
+        Err(_) => bail!("invalid value for {}: {}", flag, value),
+    }
+}
+
+fn flag_list(iter: &mut impl Iterator<Item = String>, flag: &str) -> Result<Vec<String>> {
+    let value = flag_value(iter, flag)?;
+    Ok(value.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+}
+
+/// Parses a `Name: Value` header flag.
+fn flag_header(iter: &mut impl Iterator<Item = String>, flag: &str) -> Result<(String, String)> {
+    let value = flag_value(iter, flag)?;
+    match value.split_once(':') {
+        Some((name, value)) if !name.trim().is_empty() => Ok((name.trim().to_string(), value.trim().to_string())),
+        _ => bail!("{} expects \"Name: Value\", got {:?}", flag, value),
+    }
+}
+
+/// Parses a `name=value` param override flag.
+fn flag_param(iter: &mut impl Iterator<Item = String>, flag: &str) -> Result<(String, String)> {
+    let value = flag_value(iter, flag)?;
+    match value.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => Ok((name.trim().to_string(), value.to_string())),
+        _ => bail!("{} expects name=value, got {:?}", flag, value),
+    }
+}
+
+/// Reads a JSON input file, or stdin when `path` is `-`.
+fn read_config(path: &str, what: &str) -> Result<String> {
+    if path == "-" {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).with_context(|| format!("failed to read {} from stdin", what))?;
+        return Ok(text);
+    }
+    if !std::path::Path::new(path).exists() {
+        bail!("{} file not found: {}", what, path);
+    }
+    fs::read_to_string(path).with_context(|| format!("failed to read {} file {}", what, path))
+}
+
+// =============================
+// Config File
+// =============================
+const CONFIG_FILE: &str = "ocs01.toml";
+
+/// The defaults file to read: `--config <path>`, none with `--no-config`, otherwise the
+/// first `ocs01.toml` found in the current directory or `$HOME/.config/ocs01/`.
+fn config_path(cli: &[String]) -> Result<Option<PathBuf>> {
+    if let Some(i) = cli.iter().position(|arg| arg == "--config") {
+        let Some(path) = cli.get(i + 1) else { bail!("missing value for --config") };
+        if !Path::new(path).is_file() {
+            bail!("config file not found: {}", path);
+        }
+        return Ok(Some(PathBuf::from(path)));
+    }
+    if cli.iter().any(|arg| arg == "--no-config") {
+        return Ok(None);
+    }
+    let mut candidates = vec![PathBuf::from(CONFIG_FILE)];
+    if let Some(home) = std::env::var_os("HOME") {
+        candidates.push(Path::new(&home).join(".config/ocs01").join(CONFIG_FILE));
+    }
+    Ok(candidates.into_iter().find(|path| path.is_file()))
+}
+
+/// Turns the defaults file into command-line arguments. Each top-level key is a long
+/// option without its dashes (`timeout = 30`, `log_format = "json"`, `dry_run = true`);
+/// an array repeats the option once per element, and `false` leaves a switch off.
+fn config_args(path: &Path) -> Result<Vec<String>> {
+    let text = fs::read_to_string(path).with_context(|| format!("failed to read config file {}", path.display()))?;
+    let table: toml::Table = text.parse().with_context(|| format!("invalid config file {}", path.display()))?;
+    let mut args = Vec::new();
+    for (key, value) in table {
+        let flag = format!("--{}", key.replace('_', "-"));
+        let takes_value = match option_takes_value(&flag) {
+            Some(takes_value) if !matches!(flag.as_str(), "--config" | "--no-config" | "--help") => takes_value,
+            _ => bail!("{}: unknown option '{}'", path.display(), key),
+        };
+        let values = match value {
+            toml::Value::Array(values) => values,
+            value => vec![value],
+        };
+        for value in values {
+            match value {
+                toml::Value::Boolean(on) if !takes_value => {
+                    if on {
+                        args.push(flag.clone());
+                    }
+                }
+                toml::Value::String(s) if takes_value => args.extend([flag.clone(), s]),
+                toml::Value::Integer(n) if takes_value => args.extend([flag.clone(), n.to_string()]),
+                toml::Value::Float(x) if takes_value => args.extend([flag.clone(), x.to_string()]),
+                _ if takes_value => bail!("{}: '{}' needs a string or number", path.display(), key),
+                _ => bail!("{}: '{}' is a switch and needs true or false", path.display(), key),
+            }
+        }
+    }
+    Ok(args)
+}
+
+/// Switches that cannot be combined. Turning one on from the command line also drops the
+/// other from the config file, so the two sources never conflict.
+const EXCLUSIVE: &[[&str; 2]] = &[["--only-views", "--only-calls"], ["--fail-fast", "--ignore-errors"]];
+
+/// Combines the config file's arguments with the command line's, option by option. An
+/// option on the command line replaces all of the file's values for it (lists such as
+/// `--methods` or `--header` included), and `--no-<switch>` turns off a switch either
+/// source set. Conflicting arguments that remain are rejected by [`parse_args`].
+fn merge_config(file: Vec<String>, cli: Vec<String>) -> Vec<String> {
+    let mut decided = HashSet::new();
+    let mut merged: Vec<Vec<String>> = Vec::new();
+    for group in option_groups(cli) {
+        if let Some(switch) = negated_switch(&group[0]) {
+            merged.retain(|earlier| long_option(&earlier[0]) != switch);
+            decided.insert(switch);
+            continue;
+        }
+        let flag = long_option(&group[0]);
+        if flag.starts_with("--") {
+            decided.insert(flag.to_string());
+            if let Some(pair) = EXCLUSIVE.iter().find(|pair| pair.contains(&flag)) {
+                decided.extend(pair.iter().map(|f| f.to_string()));
+            }
+        }
+        merged.push(group);
+    }
+    let defaults = option_groups(file).into_iter().filter(|group| !decided.contains(long_option(&group[0])));
+    defaults.chain(merged).flatten().collect()
+}
+
+/// Splits arguments into one group per option: the flag and, if it takes one, its value.
+/// Anything else is a group of its own.
+fn option_groups(args: Vec<String>) -> Vec<Vec<String>> {
+    let mut groups = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut group = vec![arg];
+        if option_takes_value(long_option(&group[0])) == Some(true) {
+            group.extend(args.next());
+        }
+        groups.push(group);
+    }
+    groups
+}
+
+/// The long form of a short option; anything else is returned as is.
+fn long_option(arg: &str) -> &str {
+    match arg {
+        "-i" => "--interactive",
+        "-q" => "--quiet",
+        "-v" | "-vv" => "--verbose",
+        "-h" => "--help",
+        other => other,
+    }
+}
+
+/// The switch `arg` turns off if it is `--no-<switch>`. Options that are themselves named
+/// `--no-...`, such as `--no-config`, are left alone.
+fn negated_switch(arg: &str) -> Option<String> {
+    if option_takes_value(arg).is_some() {
+        return None;
+    }
+    let switch = format!("--{}", arg.strip_prefix("--no-")?);
+    (option_takes_value(&switch) == Some(false) && !matches!(switch.as_str(), "--help" | "--no-config")).then_some(switch)
+}
+
+/// Every long option, with whether it takes a value. [`parse_args`] rejects options that
+/// are not listed here, and config file keys are turned into arguments through it.
+const OPTIONS: &[(&str, bool)] = &[
+    ("--wallet", true),
+    ("--wallets", true),
+    ("--wallet-concurrency", true),
+    ("--every", true),
+    ("--count", true),
+    ("--metrics-file", true),
+    ("--metrics-port", true),
+    ("--metrics-bind", true),
+    ("--interface", true),
+    ("--contract", true),
+    ("--as", true),
+    ("--dry-run", false),
+    ("--simulate", false),
+    ("--timeout", true),
+    ("--connect-timeout", true),
+    ("--pool-max-idle", true),
+    ("--pool-idle-timeout", true),
+    ("--tcp-keepalive", true),
+    ("--rps", true),
+    ("--proxy", true),
+    ("--tls-cert", true),
+    ("--tls-key", true),
+    ("--tls-ca", true),
+    ("--user-agent", true),
+    ("--no-compression", false),
+    ("--header", true),
+    ("--no-addr-check", false),
+    ("--wait", false),
+    ("--confirm", false),
+    ("--confirm-no-tty", true),
+    ("--wait-timeout", true),
+    ("--poll-interval", true),
+    ("--methods", true),
+    ("--skip", true),
+    ("--only-views", false),
+    ("--only-calls", false),
+    ("--fee-per-call", true),
+    ("--no-initial-balance", false),
+    ("--low-balance", true),
+    ("--max-gas", true),
+    ("--time-offset", true),
+    ("--min-nonce", true),
+    ("--audit-file", true),
+    ("--continue-on-nonce-error", false),
+    ("--nonce-resyncs", true),
+    ("--seed", true),
+    ("--shuffle", false),
+    ("--param", true),
+    ("--params-file", true),
+    ("--batch-views", false),
+    ("--concurrency", true),
+    ("--delay", true),
+    ("--ignore-errors", false),
+    ("--strict", false),
+    ("--fail-fast", false),
+    ("--state-file", true),
+    ("--checkpoint", true),
+    ("--resume", false),
+    ("--report", true),
+    ("--result-dir", true),
+    ("--report-max-size", true),
+    ("--log-format", true),
+    ("--output", true),
+    ("--interactive", false),
+    ("--list-methods", false),
+    ("--quiet", false),
+    ("--verbose", false),
+    ("--config", true),
+    ("--no-config", false),
+    ("--help", false),
+];
+
+/// Whether `flag` takes a value; `None` if it is not an option.
+fn option_takes_value(flag: &str) -> Option<bool> {
+    OPTIONS.iter().find(|(name, _)| *name == flag).map(|&(_, takes_value)| takes_value)
+}
+
+// =============================
+// Wallet Key
+// =============================
+fn read_passphrase() -> Result<Zeroizing<String>> {
+    if let Ok(passphrase) = std::env::var("OCS01_PASSPHRASE") {
+        return Ok(Zeroizing::new(passphrase));
+    }
+    let passphrase = rpassword::prompt_password("🔑 Wallet passphrase: ")?;
+    Ok(Zeroizing::new(passphrase))
+}
+
+/// The encoded key, taken from `OCS01_PRIVATE_KEY`, then `priv_file`, then `priv`.
+fn read_encoded_key(wallet: &Wallet) -> Result<Zeroizing<String>> {
+    if let Ok(key) = std::env::var("OCS01_PRIVATE_KEY") {
+        return Ok(Zeroizing::new(key));
+    }
+    if let Some(path) = &wallet.priv_file {
+        let key = fs::read_to_string(path).with_context(|| format!("failed to read priv_file {}", path))?;
+        return Ok(Zeroizing::new(key.trim().to_string()));
+    }
+    match &wallet.priv_ {
+        Some(key) => Ok(Zeroizing::new(key.clone())),
+        None => bail!("no private key: set OCS01_PRIVATE_KEY, or mnemonic, priv_file or priv in the wallet file"),
+    }
+}
+
+fn load_key_bytes(wallet: &Wallet) -> Result<Zeroizing<Vec<u8>>> {
+    if std::env::var_os("OCS01_PRIVATE_KEY").is_none()
+        && let Some(phrase) = &wallet.mnemonic
+    {
+        if wallet.enc {
+            bail!("enc does not apply to mnemonic; remove one of them from the wallet file");
+        }
+        let path = wallet.derivation_path.as_deref().unwrap_or(DEFAULT_DERIVATION_PATH);
+        return Ok(Zeroizing::new(key_from_mnemonic(phrase, path)?.to_vec()));
+    }
+    let encoded = read_encoded_key(wallet)?;
+    if wallet.enc {
+        let passphrase = read_passphrase()?;
+        Ok(decrypt_private_key(&encoded, &passphrase)?)
+    } else {
+        Ok(decode_private_key(&encoded)?)
+    }
+}
+
+// =============================
+// Method Selection
+// =============================
+fn select_methods(methods: Vec<Method>, only: &[String], skip: &[String]) -> Vec<Method> {
+    let unknown: Vec<&String> = only.iter().chain(skip)
+        .filter(|name| !methods.iter().any(|m| &m.name == *name))
+        .collect();
+    if !unknown.is_empty() {
+        let available: Vec<&str> = methods.iter().map(|m| m.name.as_str()).collect();
+        eprintln!("⚠ Unknown method(s): {:?}. Available: {}", unknown, available.join(", "));
+    }
+
+    methods.into_iter()
+        .filter(|m| only.is_empty() || only.contains(&m.name))
+        .filter(|m| !skip.contains(&m.name))
+        .collect()
+}
+
+/// Pins every param named in `overrides` to the given value by making it the param's example,
+/// which `generate_params` always prefers over a random value.
+fn apply_param_overrides(methods: &mut [Method], overrides: &[(String, String)]) -> Result<()> {
+    for (name, value) in overrides {
+        let mut found = false;
+        for p in methods.iter_mut().flat_map(|m| m.params.iter_mut()).filter(|p| &p.name == name) {
+            p.example = Some(value.clone());
+            found = true;
+        }
+        if !found {
+            bail!("--param {}: no selected method has a param named {}", name, name);
+        }
+    }
+    Ok(())
+}
+
+/// Reads the `--params-file` map of method name to exact params. Each list must match its
+/// method's declared param count; names not in the interface are ignored with a warning.
+fn read_params_file(path: &str, methods: &[Method]) -> Result<BTreeMap<String, Vec<String>>> {
+    let fixed: BTreeMap<String, Vec<String>> = serde_json::from_str(&read_config(path, "params")?)
+        .with_context(|| format!("invalid params file {} (expected {{\"method\": [\"param\", ...]}})", path))?;
+    for (name, params) in &fixed {
+        match methods.iter().find(|m| &m.name == name) {
+            Some(method) => check_param_count(method, params).with_context(|| format!("invalid params file {}", path))?,
+            None => eprintln!("⚠ {}: {} is not a selected method; its params are unused", path, name),
+        }
+    }
+    Ok(fixed)
+}
+
+/// The params for one run of `method`: the `--params-file` entry if there is one,
+/// otherwise generated ones.
+fn method_params(interface: &Interface, method: &Method, wallet: &Wallet, fixed: &BTreeMap<String, Vec<String>>, rng: &mut StdRng) -> Vec<String> {
+    let ctx = ParamContext { addr: &wallet.addr, contract: interface.contract_of(method) };
+    match fixed.get(&method.name) {
+        Some(params) => params.clone(),
+        None => generate_params(&method.params, &ctx, rng),
+    }
+}
+
+/// Method indices in a random order that keeps every position's type: views are shuffled
+/// among the view positions and calls among the call positions.
+fn shuffle_within_types(methods: &[Method], rng: &mut StdRng) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..methods.len()).collect();
+    for &kind in METHOD_TYPES {
+        let slots: Vec<usize> = (0..methods.len()).filter(|&i| methods[i].method_type == kind).collect();
+        let mut shuffled = slots.clone();
+        shuffled.shuffle(rng);
+        for (slot, i) in slots.into_iter().zip(shuffled) {
+            order[slot] = i;
+        }
+    }
+    order
+}
+
+// =============================
+// Nonce State
+// =============================
+/// Reads the `--state-file` map of address to last submitted nonce; a missing file is empty.
+fn read_nonce_state(path: &str) -> Result<BTreeMap<String, u64>> {
+    if !std::path::Path::new(path).exists() {
+        return Ok(BTreeMap::new());
+    }
+    serde_json::from_str(&read_config(path, "state")?).with_context(|| format!("failed to parse state file {}", path))
+}
+
+/// Records `nonce` as the last one submitted from `addr`, replacing the file atomically.
+fn save_nonce_state(path: &str, addr: &str, nonce: u64) -> Result<()> {
+    // Wallets of a --wallets run share the file; keep their read-modify-write cycles apart.
+    static STATE_LOCK: Mutex<()> = Mutex::new(());
+    let _guard = STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut state = read_nonce_state(path)?;
+    let last = state.entry(addr.to_string()).or_insert(nonce);
+    *last = (*last).max(nonce);
+
+    let tmp = format!("{}.tmp", path);
+    fs::write(&tmp, serde_json::to_string_pretty(&state)?).with_context(|| format!("failed to write {}", tmp))?;
+    fs::rename(&tmp, path).with_context(|| format!("failed to replace state file {}", path))
+}
+
+/// Saves a submitted transaction's nonce when `--state-file` is set; failures only warn.
+fn remember_nonce(args: &Args, receipt: &TxReceipt, addr: &str) {
+    if let Some(path) = &args.state_file
+        && let Err(e) = save_nonce_state(path, addr, receipt.nonce)
+    {
+        eprintln!("⚠ Could not save nonce state: {:#}", e);
+    }
+}
+
+// =============================
+// Checkpoint
+// =============================
+/// The call methods one wallet's run has submitted, kept in the `--checkpoint` file under
+/// the wallet's address so that `--resume` can skip them after an interruption. The file
+/// is not touched by dry runs and simulations.
+struct Checkpoint {
+    path: String,
+    addr: String,
+    /// [`Plan::interface_sha256`] of the run; a checkpoint for other interface files is not resumed.
+    interface_sha256: String,
+    completed: Vec<String>,
+    enabled: bool,
+}
+
+// Wallets of a --wallets run share the file; keep their read-modify-write cycles apart.
+static CHECKPOINT_LOCK: Mutex<()> = Mutex::new(());
+
+impl Checkpoint {
+    /// Under `--resume`, fails if the checkpoint was written for other interface files, since
+    /// its method names may no longer mean the same calls.
+    fn open(args: &Args, addr: &str, plan: &Plan) -> Result<Self> {
+        let interface_sha256 = plan.interface_sha256.clone();
+        let mut completed = Vec::new();
+        if args.resume {
+            let _guard = CHECKPOINT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            match read_checkpoints(&args.checkpoint)?.get(addr) {
+                Some(entry) if entry["interface_sha256"] == interface_sha256.as_str() => {
+                    completed = serde_json::from_value(entry["completed"].clone())
+                        .with_context(|| format!("invalid checkpoint for {} in {}", addr, args.checkpoint))?;
+                }
+                Some(_) => bail!(
+                    "{} (or --contract) changed since the checkpoint for {} in {} was written; restore it, or run without --resume to start from the top",
+                    args.interface, addr, args.checkpoint
+                ),
+                None => eprintln!("⚠ No checkpoint for {} in {}; starting from the top", addr, args.checkpoint),
+            }
+        }
+        Ok(Checkpoint {
+            path: args.checkpoint.clone(),
+            addr: addr.to_string(),
+            interface_sha256,
+            completed,
+            enabled: !args.dry_run && !args.simulate,
+        })
+    }
+
+    /// The checkpoint name of one run of `method`: `contract:name`, plus `#n` when it
+    /// repeats. The contract tells apart same-named methods of an interface directory.
+    fn key(interface: &Interface, method: &Method, iteration: u32) -> String {
+        let name = format!("{}:{}", interface.contract_of(method), method.name);
+        if method.repeat.unwrap_or(1) > 1 {
+            format!("{}#{}", name, iteration)
+        } else {
+            name
+        }
+    }
+
+    fn is_done(&self, key: &str) -> bool {
+        self.completed.iter().any(|done| done == key)
+    }
+
+    /// Records a submitted call; failures only warn.
+    fn mark_done(&mut self, key: String) {
+        self.completed.push(key);
+        let entry = json!({ "interface_sha256": self.interface_sha256, "completed": self.completed });
+        self.save(Some(entry));
+    }
+
+    /// Drops this wallet's entry once a run has gone through, so the next `--resume`
+    /// starts from the top.
+    fn clear(&self) {
+        self.save(None);
+    }
+
+    fn save(&self, entry: Option<serde_json::Value>) {
+        if !self.enabled {
+            return;
+        }
+        let _guard = CHECKPOINT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let result = read_checkpoints(&self.path).and_then(|mut checkpoints| {
+            match entry {
+                Some(entry) => checkpoints.insert(self.addr.clone(), entry),
+                None if checkpoints.contains_key(&self.addr) => checkpoints.remove(&self.addr),
+                None => return Ok(()),
+            };
+            if checkpoints.is_empty() {
+                return fs::remove_file(&self.path).with_context(|| format!("failed to remove checkpoint file {}", self.path));
+            }
+            let tmp = format!("{}.tmp", self.path);
+            fs::write(&tmp, serde_json::to_string_pretty(&checkpoints)?).with_context(|| format!("failed to write {}", tmp))?;
+            fs::rename(&tmp, &self.path).with_context(|| format!("failed to replace checkpoint file {}", self.path))
+        });
+        if let Err(e) = result {
+            eprintln!("⚠ Could not save checkpoint: {:#}", e);
+        }
+    }
+}
+
+/// Reads the `--checkpoint` file, a map of address to checkpoint; a missing file is empty.
+fn read_checkpoints(path: &str) -> Result<BTreeMap<String, serde_json::Value>> {
+    if !std::path::Path::new(path).exists() {
+        return Ok(BTreeMap::new());
+    }
+    serde_json::from_str(&read_config(path, "checkpoint")?).with_context(|| format!("failed to parse checkpoint file {}", path))
+}
 
 // =============================
-// Struct Definitions
+// Setup
 // =============================
-#[derive(Deserialize)]
-struct Wallet {
-    #[serde(rename = "priv")]
-    priv_: String,
-    addr: String,
-    rpc: String,
+fn load_wallet(args: &Args) -> Result<Wallet> {
+    serde_json::from_str(&read_config(&args.wallet, "wallet")?)
+        .with_context(|| format!("invalid wallet file {}", args.wallet))
 }
 
-#[derive(Deserialize)]
-struct Param {
-    name: String,
-    #[serde(rename = "type")]
-    param_type: String,
-    example: Option<String>,
-    max: Option<u64>,
+/// The interface a `run` executes, with what is derived from its files once up front.
+struct Plan {
+    interface: Interface,
+    /// SHA-256 of the raw interface file (or of every file in the directory, with their
+    /// names) and `--contract`, as read before any filtering.
+    interface_sha256: String,
+    /// The `--params-file` entries, read once even when the file is stdin or runs repeat.
+    fixed_params: BTreeMap<String, Vec<String>>,
 }
 
-#[derive(Deserialize)]
-struct Method {
-    name: String,
-    label: String,
-    params: Vec<Param>,
-    #[serde(rename = "type")]
-    method_type: String,
+fn load_plan(args: &Args) -> Result<Plan> {
+    let mut digest = Sha256::new();
+    let interface = read_interface(args, &mut digest)?;
+    let interface_sha256 = digest.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    let fixed_params = match &args.params_file {
+        Some(path) => read_params_file(path, &interface.methods)?,
+        None => BTreeMap::new(),
+    };
+    Ok(Plan { interface, interface_sha256, fixed_params })
 }
 
-#[derive(Deserialize)]
-struct Interface {
-    contract: String,
-    methods: Vec<Method>,
+fn load_interface(args: &Args) -> Result<Interface> {
+    read_interface(args, &mut Sha256::new())
 }
 
-#[derive(Deserialize)]
-struct BalanceResponse {
-    balance_raw: String,
-    nonce: u64,
+/// Reads `--interface` and applies the method filters and overrides, feeding every raw
+/// file read and `--contract` into `digest`.
+fn read_interface(args: &Args, digest: &mut Sha256) -> Result<Interface> {
+    let mut interface = if std::path::Path::new(&args.interface).is_dir() {
+        read_interface_dir(&args.interface, digest)?
+    } else {
+        read_interface_file(&args.interface, digest)?
+    };
+    if let Some(contract) = &args.contract {
+        digest.update(b"\0--contract\0");
+        digest.update(contract.as_bytes());
+        interface.contract = contract.clone();
+        for m in &mut interface.methods {
+            m.contract = None;
+        }
+    }
+    interface.methods = select_methods(interface.methods, &args.methods, &args.skip);
+    if let Some(only) = args.only_type {
+        interface.methods.retain(|m| m.method_type == only);
+    }
+    apply_param_overrides(&mut interface.methods, &args.param_overrides)?;
+    Ok(interface)
 }
 
-// =============================
-// Helper: API Call
-// =============================
-fn api_call<T: for<'de> Deserialize<'de>>(
-    client: &Client,
-    method: &str,
-    url: &str,
-    data: Option<serde_json::Value>
-) -> Result<T> {
-    let resp = match method {
-        "GET" => client.get(url).send()?,
-        "POST" => client.post(url).json(&data).send()?,
-        _ => bail!("Unsupported HTTP method"),
+/// Whether `interface` has no methods left to run, which is a warning, or an error under
+/// `--strict`, rather than a run that does nothing and reports success.
+fn nothing_to_run(args: &Args, interface: &Interface) -> Result<bool> {
+    if !interface.methods.is_empty() {
+        return Ok(false);
+    }
+    let why = if args.methods.is_empty() && args.skip.is_empty() && args.only_type.is_none() {
+        format!("{} has no methods", args.interface)
+    } else {
+        format!("the method filters left none of the methods in {}", args.interface)
     };
+    if args.strict {
+        bail!("{}; nothing to run", why);
+    }
+    eprintln!("⚠ {}; nothing to run", why);
+    Ok(true)
+}
+
+fn read_interface_file(path: &str, digest: &mut Sha256) -> Result<Interface> {
+    let text = read_config(path, "interface")?;
+    digest.update(text.as_bytes());
+    let interface: Interface = serde_json::from_str(&text)
+        .with_context(|| format!("invalid interface file {}", path))?;
+    interface.validate()
+        .with_context(|| format!("invalid interface file {}", path))?;
+    Ok(interface)
+}
+
+/// Combines every `*.json` interface file in `dir`, in file name order. Each method is
+/// tagged with its file's contract unless it sets its own.
+fn read_interface_dir(dir: &str, digest: &mut Sha256) -> Result<Interface> {
+    let mut paths: Vec<std::path::PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("failed to read interface directory {}", dir))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    paths.retain(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "json"));
+    paths.sort();
 
-    if resp.status().as_u16() >= 400 {
-        bail!("api error: {}", resp.text()?);
+    let mut combined: Option<Interface> = None;
+    for path in paths {
+        if let Some(name) = path.file_name() {
+            digest.update(b"\0");
+            digest.update(name.as_encoded_bytes());
+            digest.update(b"\0");
+        }
+        let mut interface = read_interface_file(&path.to_string_lossy(), digest)?;
+        for m in &mut interface.methods {
+            m.contract.get_or_insert_with(|| interface.contract.clone());
+        }
+        match &mut combined {
+            Some(combined) => combined.methods.append(&mut interface.methods),
+            None => combined = Some(interface),
+        }
     }
+    combined.with_context(|| format!("no *.json interface files in {}", dir))
+}
 
-    Ok(resp.json()?)
+/// Prints the interface's methods as a table: type, name, params with their types, label,
+/// and the contract when there is more than one.
+fn print_methods(interface: &Interface) {
+    let multi_contract = contracts(interface).len() > 1;
+    let rows: Vec<[String; 5]> = interface.methods.iter()
+        .map(|m| {
+            let params: Vec<String> = m.params.iter().map(|p| format!("{}: {}", p.name, p.param_type)).collect();
+            let name = match m.repeat {
+                Some(n) if n > 1 => format!("{} (x{})", m.name, n),
+                _ => m.name.clone(),
+            };
+            [m.method_type.to_string(), name, format!("({})", params.join(", ")), m.label.clone(), interface.contract_of(m).to_string()]
+        })
+        .collect();
+    let header = ["TYPE", "NAME", "PARAMS", "LABEL", "CONTRACT"].map(String::from);
+    let columns = if multi_contract { 5 } else { 4 };
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| std::iter::once(&header).chain(&rows).map(|r| r[c].chars().count()).max().unwrap_or(0))
+        .collect();
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells: Vec<String> = (0..columns).map(|c| format!("{:<w$}", row[c], w = widths[c])).collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
 }
 
-// =============================
-// Helper: Balance
-// =============================
-fn get_balance(client: &Client, api_url: &str, addr: &str) -> Result<(f64, u64)> {
-    let balance: BalanceResponse = api_call(
-        client,
-        "GET",
-        &format!("{}/balance/{}", api_url, addr),
-        None
-    )?;
-    let oct_balance = balance.balance_raw.parse::<f64>()? / 1_000_000.0;
-    Ok((oct_balance, balance.nonce))
+/// Distinct contracts the interface's methods are called on, in order of appearance.
+fn contracts(interface: &Interface) -> Vec<&str> {
+    let mut contracts: Vec<&str> = Vec::new();
+    for m in &interface.methods {
+        let contract = interface.contract_of(m);
+        if !contracts.contains(&contract) {
+            contracts.push(contract);
+        }
+    }
+    contracts
 }
 
-// =============================
-// Helper: TX Signing
-// =============================
-fn sign_tx(sk: &SigningKey, tx: &HashMap<&str, String>) -> String {
-    let blob = format!(
-        r#"{{"from":"{}","to_":"{}","amount":"{}","nonce":{},"ou":"{}","timestamp":{}}}"#,
-        tx["from"], tx["to_"], tx["amount"], tx["nonce"], tx["ou"], tx["timestamp"]
-    );
-    let sig = sk.sign(blob.as_bytes());
-    general_purpose::STANDARD.encode(sig.to_bytes())
+fn load_signing_key(wallet: &Wallet) -> Result<SigningKey> {
+    let sk_bytes = load_key_bytes(wallet)?;
+    let sk_array: Zeroizing<[u8; 32]> = match sk_bytes.as_slice().try_into() {
+        Ok(bytes) => Zeroizing::new(bytes),
+        Err(_) if sk_bytes.len() == 64 => {
+            bail!("private key must be 32 bytes, got 64; this looks like an ed25519 keypair, use its first 32 bytes (the seed)")
+        }
+        Err(_) => bail!("private key must be 32 bytes, got {}", sk_bytes.len()),
+    };
+    Ok(SigningKey::from_bytes(&sk_array))
+}
+
+fn load_account(args: &Args, wallet: &Wallet) -> Result<Account> {
+    let mut account = Account::new(load_signing_key(wallet)?, wallet.addr.clone());
+    if let Some(path) = &args.state_file
+        && let Some(&floor) = read_nonce_state(path)?.get(&account.addr)
+    {
+        account.set_nonce_floor(floor);
+    }
+    if let Some(secs) = args.time_offset {
+        account.set_time_offset((secs * 1e6).round() as i64);
+    }
+
+    let derived = derive_address(&account.sk.verifying_key());
+    if derived != wallet.addr {
+        let msg = format!("wallet addr {} does not match the private key (expected {})", wallet.addr, derived);
+        if !args.no_addr_check {
+            bail!("{}; pass --no-addr-check to run anyway", msg);
+        }
+        eprintln!("⚠ {}", msg);
+    }
+    Ok(account)
+}
+
+/// The [`TxOptions`] for `args`. The `--audit-file` is opened once and shared, so every
+/// wallet of a fleet appends to the same hash chain.
+fn tx_options(args: &Args) -> Result<TxOptions> {
+    static AUDIT_LOG: Mutex<Option<Arc<AuditLog>>> = Mutex::new(None);
+    let audit = match &args.audit_file {
+        Some(path) => {
+            let mut shared = AUDIT_LOG.lock().unwrap_or_else(|e| e.into_inner());
+            if shared.is_none() {
+                let log = AuditLog::open(path).map_err(anyhow::Error::from).with_context(|| format!("cannot open audit file {}", path))?;
+                *shared = Some(Arc::new(log));
+            }
+            shared.clone()
+        }
+        None => None,
+    };
+    Ok(TxOptions { dry_run: args.dry_run, min_nonce: args.min_nonce, audit })
+}
+
+fn retry_policy(args: &Args) -> RetryPolicy {
+    let nonce_resyncs = if args.continue_on_nonce_error { args.nonce_resyncs } else { 0 };
+    RetryPolicy { nonce_resyncs, ..RetryPolicy::default() }
+}
+
+const DEFAULT_USER_AGENT: &str = concat!("ocs01-auto/", env!("CARGO_PKG_VERSION"));
+
+fn build_rpc(args: &Args, wallet: &Wallet) -> Result<Rpc> {
+    let timeout = args.timeout.or(wallet.timeout).unwrap_or(100);
+    let connect_timeout = args.connect_timeout.or(wallet.connect_timeout).unwrap_or(10);
+    // HTTP keep-alive is always on: one client serves the whole run, so sequential calls
+    // to a node reuse its pooled connection instead of connecting (and handshaking) again.
+    let pool_max_idle = args.pool_max_idle.or(wallet.pool_max_idle_per_host).unwrap_or(8);
+    let pool_idle_timeout = args.pool_idle_timeout.or(wallet.pool_idle_timeout).unwrap_or(90);
+    let tcp_keepalive = args.tcp_keepalive.or(wallet.tcp_keepalive).unwrap_or(60);
+    let mut builder = Client::builder()
+        .timeout(Duration::from_secs(timeout))
+        .connect_timeout(Duration::from_secs(connect_timeout))
+        .pool_max_idle_per_host(pool_max_idle)
+        .pool_idle_timeout(Duration::from_secs(pool_idle_timeout))
+        .tcp_keepalive((tcp_keepalive > 0).then(|| Duration::from_secs(tcp_keepalive)))
+        .user_agent(args.user_agent.clone().unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()));
+    // Compressed responses are requested and decoded by default.
+    if args.no_compression {
+        builder = builder.no_gzip().no_deflate().no_brotli();
+    }
+    // Without --proxy, reqwest already honors HTTPS_PROXY, HTTP_PROXY and NO_PROXY.
+    if let Some(url) = &args.proxy {
+        builder = builder.proxy(reqwest::Proxy::all(url).with_context(|| format!("invalid proxy URL {}", url))?);
+    }
+    // TLS flags override the wallet.json fields of the same name.
+    match (args.tls_cert.as_ref().or(wallet.tls_cert.as_ref()), args.tls_key.as_ref().or(wallet.tls_key.as_ref())) {
+        (Some(cert), Some(key)) => {
+            let cert_pem = fs::read(cert).with_context(|| format!("failed to read TLS certificate {}", cert))?;
+            let key_pem = fs::read(key).with_context(|| format!("failed to read TLS key {}", key))?;
+            let identity = reqwest::Identity::from_pkcs8_pem(&cert_pem, &key_pem)
+                .with_context(|| format!("invalid TLS client certificate {} or key {}", cert, key))?;
+            builder = builder.identity(identity);
+        }
+        (None, None) => {}
+        _ => bail!("a TLS client certificate needs both tls_cert and tls_key"),
+    }
+    if let Some(ca) = args.tls_ca.as_ref().or(wallet.tls_ca.as_ref()) {
+        let pem = fs::read(ca).with_context(|| format!("failed to read TLS root CA {}", ca))?;
+        let cert = reqwest::Certificate::from_pem(&pem).with_context(|| format!("invalid TLS root CA {}", ca))?;
+        builder = builder.add_root_certificate(cert);
+    }
+    // Headers from --header override those of the same name in wallet.json.
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (name, value) in wallet.headers.iter().chain(args.headers.iter().map(|(n, v)| (n, v))) {
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .with_context(|| format!("invalid header name {:?}", name))?;
+        let mut value = reqwest::header::HeaderValue::from_str(value)
+            .with_context(|| format!("invalid value for header {}", name))?;
+        value.set_sensitive(true);
+        headers.insert(name, value);
+    }
+    let client = builder.default_headers(headers).build()?;
+    let rpc = Rpc::new(client, wallet.rpc.clone()).context("the wallet file sets no rpc")?;
+    match args.rps {
+        Some(rps) => Ok(rpc.with_rate_limit(rps)?),
+        None => Ok(rpc),
+    }
+}
+
+/// Asks the node for its network and enforces `expected_network` from the wallet. Without
+/// an expectation, a failed query only warns.
+fn check_network(rpc: &Rpc, wallet: &Wallet) -> Result<Option<NetworkInfo>> {
+    let network = match (get_network(rpc), &wallet.expected_network) {
+        (Ok(network), _) => network,
+        (Err(e), None) => {
+            eprintln!("⚠ Could not query the node's network: {}", e);
+            None
+        }
+        (Err(e), Some(_)) => return Err(anyhow::Error::from(e).context("failed to query the node's network")),
+    };
+    if let Some(expected) = &wallet.expected_network {
+        match &network {
+            Some(network) if network.matches(expected) => {}
+            Some(network) => bail!("network mismatch: wallet.json expects {} but the node reports {}", expected, network),
+            None => bail!("cannot check expected_network {}: the node has no /network or /info endpoint", expected),
+        }
+    }
+    Ok(network)
 }
 
 // =============================
-// View Call
+// Commands
 // =============================
-fn view_call(client: &Client, api_url: &str, contract: &str, method: &str, params: &[String], caller: &str) -> Result<String> {
-    let res: serde_json::Value = api_call(
-        client,
-        "POST",
-        &format!("{}/contract/call-view", api_url),
-        Some(json!({
-            "contract": contract,
-            "method": method,
-            "params": params,
-            "caller": caller
-        }))
-    )?;
-
-    if res["status"] == "success" {
-        Ok(res["result"].as_str().unwrap_or("null").to_string())
+fn cmd_methods(args: &Args) -> Result<()> {
+    let interface = load_interface(args)?;
+    print_methods(&interface);
+    Ok(())
+}
+
+fn cmd_whoami(args: &Args) -> Result<()> {
+    let wallet = load_wallet(args)?;
+    let vk = load_signing_key(&wallet)?.verifying_key();
+    let addr = derive_address(&vk);
+    println!("🔑 Public key: {}", general_purpose::STANDARD.encode(vk.to_bytes()));
+    println!("👛 Address: {}", addr);
+    if !wallet.addr.is_empty() && wallet.addr != addr {
+        eprintln!("⚠ wallet addr {} does not match the private key", wallet.addr);
+    }
+    Ok(())
+}
+
+fn cmd_balance(args: &Args) -> Result<()> {
+    let wallet = load_wallet(args)?;
+    let rpc = build_rpc(args, &wallet)?;
+
+    let denom = wallet.denomination()?;
+    let balance = get_balance(&rpc, &wallet.addr)?;
+    println!("👛 Address: {}", wallet.addr);
+    println!("💰 Balance: {:.6} OCT", denom.to_oct(balance.micro));
+    println!("🔢 Nonce: {}", balance.nonce);
+    Ok(())
+}
+
+/// Resolves the contract for a single ad-hoc call and checks `params` against the method's
+/// definition when the interface file is available.
+fn resolve_single_call(args: &Args, method: &str, params: &[String]) -> Result<(String, Option<Method>)> {
+    // With --contract the interface file is optional, but stdin was asked for explicitly.
+    let interface = if args.contract.is_none() || args.interface == "-" || std::path::Path::new(&args.interface).exists() {
+        Some(load_interface(args)?)
     } else {
-        bail!("Error: {:?}", res)
+        None
+    };
+    let contract = match (&args.contract, &interface) {
+        (Some(contract), _) => contract.clone(),
+        (None, Some(interface)) => match interface.methods.iter().find(|m| m.name == method) {
+            Some(def) => interface.contract_of(def).to_string(),
+            None => interface.contract.clone(),
+        },
+        (None, None) => unreachable!("interface is loaded when --contract is absent"),
+    };
+
+    let definition = interface.and_then(|i| i.methods.into_iter().find(|m| m.name == method));
+    match &definition {
+        Some(def) => check_param_count(def, params)?,
+        None => eprintln!("⚠ {} is not in the interface file; params are not checked", method),
     }
+    Ok((contract, definition))
 }
 
-// =============================
-// TX Call with Retry
-// =============================
-fn call_contract_tx(client: &Client, api_url: &str, sk: &SigningKey, from: &str, contract: &str, method: &str, params: &[String]) -> Result<String> {
-    for attempt in 1..=3 {
-        match try_send_tx(client, api_url, sk, from, contract, method, params) {
-            Ok(hash) => return Ok(hash),
-            Err(e) => {
-                eprintln!("⚠ Attempt {}/3 failed: {}", attempt, e);
-                std::thread::sleep(std::time::Duration::from_secs(2));
+/// The `caller` for a view of `method`: `--as`, else the method's `caller`, else the wallet.
+fn view_caller<'a>(args: &'a Args, method: Option<&'a Method>, wallet: &'a Wallet) -> &'a str {
+    args.view_as.as_deref()
+        .or_else(|| method.and_then(|m| m.caller.as_deref()))
+        .unwrap_or(&wallet.addr)
+}
+
+fn check_param_count(def: &Method, params: &[String]) -> Result<()> {
+    if def.params.len() != params.len() {
+        let names: Vec<&str> = def.params.iter().map(|p| p.name.as_str()).collect();
+        bail!("{} takes {} params ({}), got {}", def.name, def.params.len(), names.join(", "), params.len());
+    }
+    Ok(())
+}
+
+fn cmd_view(args: &Args, method: &str, params: &[String]) -> Result<()> {
+    let wallet = load_wallet(args)?;
+    let (contract, definition) = resolve_single_call(args, method, params)?;
+    let rpc = build_rpc(args, &wallet)?;
+
+    let timeout = definition.as_ref().and_then(Method::request_timeout);
+    let retry = retry_policy(args);
+    let caller = view_caller(args, definition.as_ref(), &wallet);
+    let result = with_retries(&retry, || view_call_with_timeout(&rpc, &contract, method, params, caller, timeout))?;
+    println!("{}", result_text(&result));
+    Ok(())
+}
+
+fn cmd_call(args: &Args, method: &str, params: &[String]) -> Result<()> {
+    let wallet = load_wallet(args)?;
+    let (contract, definition) = resolve_single_call(args, method, params)?;
+    if let Some(def) = &definition && def.method_type != MethodType::Call {
+        bail!("{} is a {} method, not a call; use the view command instead", method, def.method_type);
+    }
+    let mut account = load_account(args, &wallet)?;
+    let opts = tx_options(args)?;
+    let rpc = build_rpc(args, &wallet)?;
+    if let Some(network) = check_network(&rpc, &wallet)? {
+        eprintln!("🌐 Network: {}", network);
+    }
+
+    let call = ContractCall {
+        contract: &contract,
+        method,
+        params,
+        ou: definition.as_ref().and_then(|d| d.ou).unwrap_or(DEFAULT_OU),
+        amount: definition.as_ref().and_then(|d| d.amount).unwrap_or(0),
+        timeout: definition.as_ref().and_then(Method::request_timeout),
+    };
+    if args.simulate {
+        let simulation = with_retries(&retry_policy(args), || simulate_tx(&rpc, &mut account, &call))?;
+        println!("{}", result_text(&simulation.result));
+        if let Some(gas) = simulation.gas_used {
+            eprintln!("🔮 Simulated, gas used {}", gas);
+        }
+        return Ok(());
+    }
+    if !confirm_call(args, method, definition.as_ref().map(|d| d.label.as_str()), &mut false)? {
+        bail!("{} declined; nothing submitted", method);
+    }
+    let receipt = match call_contract_tx(&rpc, &mut account, &call, &opts, &retry_policy(args))? {
+        Submission::Sent(receipt) => receipt,
+        Submission::DryRun(body) => return print_dry_run(args, &body),
+    };
+    remember_nonce(args, &receipt, &account.addr);
+    println!("{}", receipt.tx_hash);
+    if let Some(link) = wallet.explorer_link(&receipt.tx_hash) {
+        eprintln!("🔗 {}", link);
+    }
+
+    if args.wait {
+        let timeout = Duration::from_secs(args.wait_timeout);
+        let interval = Duration::from_secs(args.poll_interval);
+        let confirmation = wait_for_confirmation(&rpc, &receipt.tx_hash, timeout, interval)?;
+        if !confirmation.is_confirmed() {
+            bail!("{} not confirmed (status: {})", receipt.tx_hash, confirmation.status);
+        }
+        match confirmation.fee() {
+            Some(fee) => eprintln!("Confirmed (fee {:.6} OCT)", wallet.denomination()?.to_oct(fee)),
+            None => eprintln!("Confirmed"),
+        }
+    }
+    Ok(())
+}
+
+const REPL_HELP: &str = "\
+  <method> [params...]  run a method; without params, params are generated
+  methods               list the interface methods
+  balance               print the balance and nonce
+  help                  show this help
+  quit                  leave (or Ctrl-D)";
+
+/// Reads `method params...` lines from stdin and runs them against one client and one
+/// nonce counter until `quit` or end of input.
+fn cmd_repl(args: &Args) -> Result<()> {
+    let wallet = load_wallet(args)?;
+    let interface = load_interface(args)?;
+    let mut account = load_account(args, &wallet)?;
+    let opts = tx_options(args)?;
+    let retry = retry_policy(args);
+    let rpc = build_rpc(args, &wallet)?;
+    let denom = wallet.denomination()?;
+    let mut rng = StdRng::seed_from_u64(args.seed.unwrap_or_else(rand::random));
+    let mut approve_all = false;
+    let fixed_params = match &args.params_file {
+        Some(path) => read_params_file(path, &interface.methods)?,
+        None => BTreeMap::new(),
+    };
+
+    println!("✅ Wallet loaded: {}", wallet.addr);
+    println!("📜 Contract: {}", contracts(&interface).join(", "));
+    println!("Type a method name and params, or `help`.");
+
+    let stdin = std::io::stdin();
+    loop {
+        print!("ocs01> ");
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.read_line(&mut line)? == 0 {
+            println!();
+            return Ok(());
+        }
+        let mut words = line.split_whitespace().map(str::to_string);
+        let Some(command) = words.next() else { continue };
+        let params: Vec<String> = words.collect();
+
+        let outcome = match command.as_str() {
+            "quit" | "exit" => return Ok(()),
+            "help" => {
+                println!("{}", REPL_HELP);
+                Ok(())
+            }
+            "methods" => {
+                print_methods(&interface);
+                Ok(())
             }
+            "balance" => get_balance(&rpc, &wallet.addr)
+                .map(|b| println!("💰 {:.6} OCT, nonce {}", denom.to_oct(b.micro), b.nonce))
+                .map_err(anyhow::Error::from),
+            name => match interface.methods.iter().find(|m| m.name == name) {
+                None => Err(anyhow::anyhow!("unknown method {}; type `methods` for the list", name)),
+                Some(method) => {
+                    let params = if params.is_empty() && !method.params.is_empty() {
+                        let generated = method_params(&interface, method, &wallet, &fixed_params, &mut rng);
+                        println!("🎲 params: {}", generated.join(" "));
+                        generated
+                    } else {
+                        params
+                    };
+                    check_param_count(method, &params).and_then(|()| {
+                        if method.method_type == MethodType::View {
+                            let result = with_retries(&retry, || view_call_with_timeout(
+                                &rpc, interface.contract_of(method), &method.name, &params, view_caller(args, Some(method), &wallet), method.request_timeout()
+                            ))?;
+                            println!("{}", result_text(&result));
+                            return Ok(());
+                        }
+                        let call = ContractCall {
+                            contract: interface.contract_of(method),
+                            method: &method.name,
+                            params: &params,
+                            ou: method.ou.unwrap_or(DEFAULT_OU),
+                            amount: method.amount.unwrap_or(0),
+                            timeout: method.request_timeout(),
+                        };
+                        if !confirm_call(args, &method.name, Some(&method.label), &mut approve_all)? {
+                            println!("⏭ Declined");
+                            return Ok(());
+                        }
+                        match call_contract_tx(&rpc, &mut account, &call, &opts, &retry)? {
+                            Submission::Sent(receipt) => {
+                                remember_nonce(args, &receipt, &account.addr);
+                                println!("TX Hash: {}", receipt.tx_hash);
+                                if let Some(link) = wallet.explorer_link(&receipt.tx_hash) {
+                                    println!("🔗 {}", link);
+                                }
+                                Ok(())
+                            }
+                            Submission::DryRun(body) => print_dry_run(args, &body),
+                        }
+                    })
+                }
+            },
+        };
+        if let Err(e) = outcome {
+            println!("Error: {:#}", e);
         }
     }
-    bail!("All retries failed")
 }
 
-fn try_send_tx(client: &Client, api_url: &str, sk: &SigningKey, from: &str, contract: &str, method: &str, params: &[String]) -> Result<String> {
-    let (_, nonce) = get_balance(client, api_url, from)?;
-    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs_f64();
+/// Shows and records the request time of the current method run, retries included.
+fn note_latency(args: &Args, report: &Report, summary: &mut Summary, latency: Duration) {
+    say!(args, "⏱ {} ms", latency.as_millis());
+    report.set_latency(latency);
+    summary.latencies.push(latency);
+}
+
+/// Outcome of [`run_wallet`] for one wallet.
+struct WalletRun {
+    /// The balance at the start, unless it was not fetched; see `--no-initial-balance`.
+    balance: Option<Balance>,
+    summary: Summary,
+    /// Number of method runs planned, including repeats.
+    planned: usize,
+    /// Why the run stopped before the end (`--max-gas`, `--fail-fast`, `--min-nonce`), if
+    /// it did.
+    stop: Option<String>,
+}
+
+fn cmd_run(args: &Args) -> Result<()> {
+    let wallet = load_wallet(args)?;
+    let plan = load_plan(args)?;
+    if nothing_to_run(args, &plan.interface)? {
+        return Ok(());
+    }
+    let report = Report::open(&args.report, args.report_max_size, args.log_format, args.dry_run)?;
+    install_interrupt_handler()?;
+    serve_metrics(args)?;
+    let mut session = connect(args, &wallet)?;
+    let Some(Interval(every)) = args.every else {
+        let run = run_wallet(args, &wallet, &plan, &report, &mut session);
+        record_metrics(args, &wallet.addr, run.as_ref().ok())?;
+        return finish_run(args, &wallet, &report, run?);
+    };
+
+    // With --every, a failed run is reported and the schedule goes on.
+    let (mut runs, mut failed_runs) = (0, 0);
+    loop {
+        runs += 1;
+        let of = args.count.map(|n| format!(" of {}", n)).unwrap_or_default();
+        say!(args, "🔁 Run {}{}", runs, of);
+        let run = run_wallet(args, &wallet, &plan, &report, &mut session);
+        let result = record_metrics(args, &wallet.addr, run.as_ref().ok())
+            .and(run)
+            .and_then(|run| finish_run(args, &wallet, &report, run));
+        if let Err(e) = result {
+            failed_runs += 1;
+            eprintln!("❌ Run {}: {:#}", runs, e);
+        }
+        if args.count.is_some_and(|n| runs >= n) {
+            break;
+        }
+        say!(args, "⏳ Next run in {:?}", every);
+        if !sleep_unless_interrupted(every) {
+            report.flush()?;
+            eprintln!("⏹ Stopped after {} runs", runs);
+            std::process::exit(EXIT_INTERRUPTED);
+        }
+    }
+    if failed_runs > 0 {
+        bail!("{} of {} runs failed", failed_runs, runs);
+    }
+    Ok(())
+}
 
-    let mut tx = HashMap::new();
-    tx.insert("from", from.to_string());
-    tx.insert("to_", contract.to_string());
-    tx.insert("amount", "0".to_string());
-    tx.insert("nonce", (nonce + 1).to_string());
-    tx.insert("ou", "1".to_string());
-    tx.insert("timestamp", timestamp.to_string());
+/// Under `--confirm`, asks whether to submit the call to method `name`, described by its
+/// `label` when the interface has one, and reads the answer from stdin. Answering `a` sets
+/// `approve_all`, which approves the rest without asking. Dry runs and simulations are not
+/// asked about, and without a terminal every call is declined (the other
+/// `--confirm-no-tty` choice is rejected up front).
+fn confirm_call(args: &Args, name: &str, label: Option<&str>, approve_all: &mut bool) -> Result<bool> {
+    if !args.confirm || args.dry_run || args.simulate || *approve_all {
+        return Ok(true);
+    }
+    if !std::io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    let described = match label {
+        Some(label) if label != name => format!("{} ({})", name, label),
+        _ => name.to_string(),
+    };
+    loop {
+        eprint!("❓ Submit transaction for {}? [y/N/a] ", described);
+        std::io::stderr().flush()?;
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer)? == 0 {
+            return Ok(false);
+        }
+        match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => return Ok(true),
+            "a" | "all" => {
+                *approve_all = true;
+                return Ok(true);
+            }
+            "" | "n" | "no" => return Ok(false),
+            _ => eprintln!("Answer y (submit), n (skip) or a (submit this and all later calls)"),
+        }
+    }
+}
 
-    let signature = sign_tx(sk, &tx);
-    let pub_key = general_purpose::STANDARD.encode(sk.verifying_key().to_bytes());
+/// Sleeps for `duration`, returning `false` early if Ctrl-C is pressed.
+fn sleep_unless_interrupted(duration: Duration) -> bool {
+    let deadline = Instant::now() + duration;
+    while !interrupted() {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        std::thread::sleep(left.min(Duration::from_millis(200)));
+    }
+    false
+}
 
-    let res: serde_json::Value = api_call(
-        client,
-        "POST",
-        &format!("{}/call-contract", api_url),
-        Some(json!({
-            "contract": contract,
-            "method": method,
-            "params": params,
-            "caller": from,
-            "nonce": nonce + 1,
-            "timestamp": timestamp,
-            "signature": signature,
-            "public_key": pub_key
-        }))
-    )?;
+/// Prints the result of one [`run_wallet`] for `run` and turns its failures into an error.
+fn finish_run(args: &Args, wallet: &Wallet, report: &Report, run: WalletRun) -> Result<()> {
+    let summary = &run.summary;
 
-    Ok(res["tx_hash"].as_str().unwrap_or("").to_string())
+    if args.output == Output::Json {
+        let document = json!({
+            "address": wallet.addr,
+            "balance": run.balance.as_ref().map(|b| run.summary.denom.to_oct(b.micro)),
+            "balance_micro": run.balance.as_ref().map(|b| b.micro.to_string()),
+            "dry_run": args.dry_run,
+            "methods": report.take_outcomes(),
+            "summary": summary.to_json(),
+            "interrupted": interrupted(),
+        });
+        println!("{}", serde_json::to_string_pretty(&document)?);
+    }
+    if interrupted() {
+        report.flush()?;
+        eprintln!("⏹ Stopped after {} of {} methods", summary.total, run.planned);
+        std::process::exit(EXIT_INTERRUPTED);
+    }
+    if let Some(message) = run.stop {
+        bail!(message);
+    }
+    if !summary.failed.is_empty() && !args.ignore_errors {
+        bail!("{} of {} methods failed: {}", summary.failed.len(), summary.total, summary.failed.join(", "));
+    }
+    say!(args, "🎯 Done! U ALREADY COOCKEDD FRR FRR ON GOD!");
+    Ok(())
 }
 
-// =============================
-// Generate Params (random)
-// =============================
-fn generate_params(params: &[Param]) -> Vec<String> {
-    let mut rng = rand::thread_rng();
-    params.iter().map(|p| {
-        if let Some(ex) = &p.example {
-            ex.clone()
-        } else if let Some(max) = p.max {
-            rng.gen_range(1..=max).to_string()
-        } else {
-            rng.gen_range(1..=100).to_string()
+/// `run` for every wallet in `--wallets`, up to `--wallet-concurrency` at a time. Each
+/// wallet has its own account and nonce counter and its own section in the report.
+fn cmd_fleet(args: &Args) -> Result<()> {
+    let path = args.wallets.as_deref().unwrap_or_default();
+    let wallets: Vec<Wallet> = serde_json::from_str(&read_config(path, "wallets")?)
+        .with_context(|| format!("invalid wallets file {} (expected a JSON array of wallet objects)", path))?;
+    if wallets.is_empty() {
+        bail!("wallets file {} is empty", path);
+    }
+    let plan = load_plan(args)?;
+    if nothing_to_run(args, &plan.interface)? {
+        return Ok(());
+    }
+    let report = Report::open(&args.report, args.report_max_size, args.log_format, args.dry_run)?;
+    install_interrupt_handler()?;
+    serve_metrics(args)?;
+    let concurrency = args.wallet_concurrency.clamp(1, wallets.len());
+    say!(args, "👛 Running {} wallets, up to {} at a time", wallets.len(), concurrency);
+
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency {
+            let (tx, next, wallets, plan, report) = (tx.clone(), &next, &wallets, &plan, &report);
+            scope.spawn(move || {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(wallet) = wallets.get(i) else { break };
+                    if interrupted() {
+                        break;
+                    }
+                    let section = report.section();
+                    let run = connect(args, wallet).and_then(|mut session| run_wallet(args, wallet, plan, &section, &mut session));
+                    let run = record_metrics(args, &wallet.addr, run.as_ref().ok()).and(run);
+                    let outcomes = section.outcomes();
+                    let error = run.as_ref().err().map(|e| format!("{:#}", e));
+                    if let Some(e) = &error {
+                        eprintln!("❌ Wallet {}: {}", wallet.addr, e);
+                    }
+                    let run = report.write_section(&wallet.addr, section, error.as_deref()).and(run);
+                    let _ = tx.send((i, run, outcomes));
+                }
+            });
         }
-    }).collect()
+    });
+    drop(tx);
+
+    let mut runs: Vec<Option<(Result<WalletRun>, Vec<serde_json::Value>)>> = wallets.iter().map(|_| None).collect();
+    for (i, run, outcomes) in rx {
+        runs[i] = Some((run, outcomes));
+    }
+
+    let (mut total, mut succeeded, mut failed) = (0, 0, 0);
+    let mut failed_wallets = Vec::new();
+    let mut entries = Vec::new();
+    for (wallet, run) in wallets.iter().zip(runs) {
+        let entry = match run {
+            None => json!({ "address": wallet.addr, "status": "skipped" }),
+            Some((Err(e), _)) => {
+                failed_wallets.push(wallet.addr.as_str());
+                json!({ "address": wallet.addr, "status": "error", "error": format!("{:#}", e) })
+            }
+            Some((Ok(run), outcomes)) => {
+                total += run.summary.total;
+                succeeded += run.summary.succeeded;
+                failed += run.summary.failed.len();
+                if !run.summary.failed.is_empty() || run.stop.is_some() {
+                    failed_wallets.push(wallet.addr.as_str());
+                }
+                json!({
+                    "address": wallet.addr,
+                    "status": "done",
+                    "balance": run.balance.as_ref().map(|b| run.summary.denom.to_oct(b.micro)),
+                    "balance_micro": run.balance.as_ref().map(|b| b.micro.to_string()),
+                    "methods": outcomes,
+                    "summary": run.summary.to_json(),
+                    "stopped": run.stop,
+                })
+            }
+        };
+        entries.push(entry);
+    }
+    let fleet = json!({
+        "wallets": wallets.len(),
+        "failed_wallets": failed_wallets,
+        "total": total,
+        "succeeded": succeeded,
+        "failed": failed,
+    });
+    let line = format!(
+        "Fleet: {} wallets, {} with failures, {} methods, {} succeeded, {} failed",
+        wallets.len(), failed_wallets.len(), total, succeeded, failed
+    );
+    say!(args, "\n🚢 {}", line);
+    report.write_line(&match args.log_format {
+        LogFormat::Text => line,
+        LogFormat::Json => json!({ "fleet": fleet }).to_string(),
+    })?;
+    if args.output == Output::Json {
+        let document = json!({ "dry_run": args.dry_run, "wallets": entries, "fleet": fleet, "interrupted": interrupted() });
+        println!("{}", serde_json::to_string_pretty(&document)?);
+    }
+
+    if interrupted() {
+        report.flush()?;
+        std::process::exit(EXIT_INTERRUPTED);
+    }
+    if !failed_wallets.is_empty() && !args.ignore_errors {
+        bail!("{} of {} wallets had failures: {}", failed_wallets.len(), wallets.len(), failed_wallets.join(", "));
+    }
+    say!(args, "🎯 Done! U ALREADY COOCKEDD FRR FRR ON GOD!");
+    Ok(())
 }
 
-// =============================
-// Logging
-// =============================
-fn log_to_file(msg: &str) {
-    let mut file = fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open("ocs01_report.txt")
-        .unwrap();
-    writeln!(file, "{}", msg).unwrap();
+/// Prints the body a dry run signed: to stdout, or to stderr under `--output json`, which
+/// keeps stdout for its one document.
+fn print_dry_run(args: &Args, body: &serde_json::Value) -> Result<()> {
+    let text = serde_json::to_string_pretty(body)?;
+    match args.output {
+        Output::Text => println!("{}", text),
+        Output::Json => eprintln!("{}", text),
+    }
+    Ok(())
 }
 
-// =============================
-// MAIN
-// =============================
-fn main() -> Result<()> {
-    let wallet: Wallet = serde_json::from_str(&fs::read_to_string("wallet.json")?)?;
-    let interface: Interface = serde_json::from_str(&fs::read_to_string("exec_interface.json")?)?;
+/// The transaction for one run of a `call` method of `interface`.
+fn contract_call<'a>(interface: &'a Interface, method: &'a Method, params: &'a [String]) -> ContractCall<'a> {
+    ContractCall {
+        contract: interface.contract_of(method),
+        method: &method.name,
+        params,
+        ou: method.ou.unwrap_or(DEFAULT_OU),
+        amount: method.amount.unwrap_or(0),
+        timeout: method.request_timeout(),
+    }
+}
 
-    let sk_bytes = general_purpose::STANDARD.decode(&wallet.priv_)?;
-    let sk = SigningKey::from_bytes(&sk_bytes.try_into().unwrap());
-    let client = Client::builder().timeout(std::time::Duration::from_secs(100)).build()?;
+/// The account and HTTP client of one wallet, kept across `--every` runs.
+struct Session {
+    account: Account,
+    rpc: Rpc,
+}
 
-    println!("✅ Wallet loaded: {}", wallet.addr);
+fn connect(args: &Args, wallet: &Wallet) -> Result<Session> {
+    Ok(Session { account: load_account(args, wallet)?, rpc: build_rpc(args, wallet)? })
+}
+
+/// Runs the interface once for `wallet`, logging to `report`, and prints its summary.
+/// The caller installs the interrupt handler and decides the exit status.
+fn run_wallet(args: &Args, wallet: &Wallet, plan: &Plan, report: &Report, session: &mut Session) -> Result<WalletRun> {
+    let interface = &plan.interface;
+    let (account, rpc) = (&mut session.account, &session.rpc);
+    let opts = tx_options(args)?;
+    let retry = retry_policy(args);
+
+    say!(args, "✅ Wallet loaded: {}", wallet.addr);
+    let source = if args.contract.is_some() { "from --contract" } else { "from interface file" };
+    say!(args, "📜 Contract: {} ({})", contracts(interface).join(", "), source);
+    if let Some(network) = check_network(rpc, wallet)? {
+        say!(args, "🌐 Network: {}", network);
+    }
+    if opts.dry_run {
+        say!(args, "🧪 Dry run: transactions will be signed but not submitted");
+    }
+    if args.simulate {
+        say!(args, "🔮 Simulation: call methods go to /simulate-contract and nothing is committed");
+    }
+    if let Some(caller) = &args.view_as {
+        say!(args, "👤 View calls are made as {}", caller);
+    }
+
+    let denom = wallet.denomination()?;
+    let calls: Vec<&Method> = interface.methods.iter().filter(|m| m.method_type == MethodType::Call).collect();
+    let fee_micro = denom.from_oct(args.fee_per_call);
+    // A view-only run never needs the balance; with --no-initial-balance the first
+    // transaction fetches the nonce instead.
+    let balance = if calls.is_empty() || args.no_initial_balance {
+        None
+    } else {
+        Some(get_balance(rpc, &wallet.addr)?)
+    };
+    if let Some(balance) = &balance {
+        say!(args, "💰 Balance: {:.6} OCT", denom.to_oct(balance.micro));
+        if balance.micro < denom.from_oct(args.low_balance) {
+            eprintln!(
+                "⚠⚠ LOW BALANCE: {:.6} OCT is below {} OCT; call methods will likely fail for lack of gas. Fund the wallet first.",
+                denom.to_oct(balance.micro), args.low_balance
+            );
+        }
+        account.prime_nonce(balance.nonce);
+
+        let call_count: u64 = calls.iter().map(|m| m.repeat.unwrap_or(1) as u64).sum();
+        let total_ou: u64 = calls.iter().map(|m| m.ou.unwrap_or(DEFAULT_OU) * m.repeat.unwrap_or(1) as u64).sum();
+        let total_amount: u128 = calls.iter().map(|m| m.amount.unwrap_or(0) as u128 * m.repeat.unwrap_or(1) as u128).sum();
+        let estimated_cost = total_ou as u128 * fee_micro + total_amount;
+        if !opts.dry_run && !args.simulate && estimated_cost > balance.micro {
+            bail!(
+                "insufficient balance: {} call methods need about {:.6} OCT but the wallet holds {:.6} OCT",
+                call_count, denom.to_oct(estimated_cost), denom.to_oct(balance.micro)
+            );
+        }
+    }
+
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let fixed_params = &plan.fixed_params;
+    say!(args, "🎲 Param seed: {} (replay with --seed {})", seed, seed);
 
-    let (balance, _) = get_balance(&client, &wallet.rpc, &wallet.addr)?;
-    println!("💰 Balance: {:.6} OCT", balance);
+    // With --batch-views or --concurrency, all views are fetched up front; calls stay
+    // sequential for nonce order.
+    let mut prefetched: Vec<Option<Result<serde_json::Value>>> = interface.methods.iter().map(|_| None).collect();
+    if args.batch_views || args.concurrency > 1 {
+        // One batch (or thread pool) per contract and caller.
+        let group_of = |i: usize| {
+            let m = &interface.methods[i];
+            (m.method_type == MethodType::View).then(|| (interface.contract_of(m), view_caller(args, Some(m), wallet)))
+        };
+        let mut groups = Vec::new();
+        for group in (0..interface.methods.len()).filter_map(group_of) {
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+        for (contract, caller) in groups {
+            let views: Vec<usize> = (0..interface.methods.len())
+                .filter(|&i| group_of(i) == Some((contract, caller)))
+                .collect();
+            let calls: Vec<(String, Vec<String>, Option<Duration>)> = views.iter()
+                .map(|&i| {
+                    let method = &interface.methods[i];
+                    (method.name.clone(), method_params(interface, method, wallet, fixed_params, &mut rng), method.request_timeout())
+                })
+                .collect();
+            let results = if args.batch_views {
+                say!(args, "⚡ Running {} view methods in one batch request...", calls.len());
+                let calls: Vec<(String, Vec<String>)> = calls.into_iter().map(|(name, params, _)| (name, params)).collect();
+                batch_view_call(rpc, contract, &calls, caller)
+                    .map_err(anyhow::Error::from)
+                    .inspect_err(|e| eprintln!("⚠ Batch view request failed, running views one by one: {:#}", e))
+                    .unwrap_or_default()
+            } else {
+                say!(args, "⚡ Running {} view methods on up to {} threads...", calls.len(), args.concurrency);
+                view_calls_parallel(rpc, contract, &calls, caller, args.concurrency, &retry)
+            };
+            for (i, result) in views.into_iter().zip(results) {
+                prefetched[i] = Some(result.map_err(anyhow::Error::from));
+            }
+        }
+    }
 
-    for method in &interface.methods {
-        println!("▶ {}...", method.label);
-        let params = generate_params(&method.params);
-        match method.method_type.as_str() {
-            "view" => {
-                match view_call(&client, &wallet.rpc, &interface.contract, &method.name, &params, &wallet.addr) {
+    // Progress goes to stderr, and only to a terminal, so piped stdout stays clean.
+    let show_progress = !args.quiet && std::io::stderr().is_terminal();
+    let mut summary = Summary::new(denom);
+    // Each method runs `repeat` times in a row, with fresh params and nonce every time.
+    let order: Vec<usize> = if args.shuffle {
+        let order = shuffle_within_types(&interface.methods, &mut rng);
+        let names: Vec<&str> = order.iter().map(|&i| interface.methods[i].name.as_str()).collect();
+        say!(args, "🔀 Shuffled order: {}", names.join(", "));
+        order
+    } else {
+        (0..interface.methods.len()).collect()
+    };
+    let runs: Vec<(usize, u32)> = order.into_iter()
+        .flat_map(|i| (1..=interface.methods[i].repeat.unwrap_or(1)).map(move |n| (i, n)))
+        .collect();
+    // Estimated fees of the calls started so far, checked against --max-gas.
+    let max_gas_micro = args.max_gas.map(|cap| denom.from_oct(cap));
+    let mut gas_spent: u128 = 0;
+    let mut gas_exceeded = None;
+    let mut nonce_guard = None;
+    let mut failed_fast = None;
+    let mut approve_all = false;
+    let mut checkpoint = Checkpoint::open(args, &wallet.addr, plan)?;
+    if !checkpoint.completed.is_empty() {
+        say!(args, "⏩ Resuming: {} call(s) already submitted will be skipped", checkpoint.completed.len());
+    }
+    for (step, &(i, iteration)) in runs.iter().enumerate() {
+        if interrupted() {
+            break;
+        }
+        let method = &interface.methods[i];
+        let repeat = method.repeat.unwrap_or(1);
+        if method.method_type == MethodType::Call && checkpoint.is_done(&Checkpoint::key(interface, method, iteration)) {
+            say!(args, "⏭ {}: already submitted (--resume)", method.label);
+            report.start(method, (repeat > 1).then_some(iteration));
+            report.log(method, "skipped", Detail::None)?;
+            summary.skip(method);
+            continue;
+        }
+        if method.method_type == MethodType::Call && !confirm_call(args, &method.name, Some(&method.label), &mut approve_all)? {
+            say!(args, "⏭ {}: declined", method.label);
+            report.start(method, (repeat > 1).then_some(iteration));
+            report.log(method, "declined", Detail::None)?;
+            summary.skip(method);
+            continue;
+        }
+        if method.method_type == MethodType::Call && let Some(cap) = max_gas_micro {
+            let cost = method.ou.unwrap_or(DEFAULT_OU) as u128 * fee_micro;
+            if gas_spent + cost > cap {
+                gas_exceeded = Some(format!(
+                    "--max-gas {} OCT reached: {} would bring estimated fees from {:.6} to {:.6} OCT; stopped after {} of {} methods",
+                    args.max_gas.unwrap_or_default(), method.name, denom.to_oct(gas_spent),
+                    denom.to_oct(gas_spent + cost), summary.total, runs.len()
+                ));
+                break;
+            }
+            gas_spent += cost;
+        }
+        if show_progress {
+            let elapsed = summary.started.elapsed().as_secs();
+            eprintln!(
+                "⏳ [{}/{}] running {}... ({:02}:{:02} elapsed)",
+                step + 1, runs.len(), method.name, elapsed / 60, elapsed % 60
+            );
+        }
+        if repeat > 1 {
+            say!(args, "▶ {} ({}/{})...", method.label, iteration, repeat);
+        } else {
+            say!(args, "▶ {}...", method.label);
+        }
+        report.start(method, (repeat > 1).then_some(iteration));
+        let was_prefetched = prefetched[i].is_some();
+        let ok = match method.method_type {
+            MethodType::View => {
+                let result = match prefetched[i].take() {
+                    Some(result) => result,
+                    None => {
+                        let params = method_params(interface, method, wallet, fixed_params, &mut rng);
+                        let started = Instant::now();
+                        let result = with_retries(&retry, || {
+                            view_call_with_timeout(rpc, interface.contract_of(method), &method.name, &params, view_caller(args, Some(method), wallet), method.request_timeout())
+                        })
+                        .map_err(anyhow::Error::from);
+                        note_latency(args, report, &mut summary, started.elapsed());
+                        result
+                    }
+                };
+                match result {
                     Ok(result) => {
-                        println!("Result: {}", result);
-                        log_to_file(&format!("{}: {}", method.label, result));
+                        say!(args, "Result: {}", result_text(&result));
+                        report.log(method, "success", Detail::Result(&result))?;
+                        save_result(args, &wallet.addr, method, (repeat > 1).then_some(iteration), &result);
+                        true
+                    }
+                    Err(e) => {
+                        say!(args, "Error: {:#}", e);
+                        report.log(method, "error", Detail::Error(&format!("{:#}", e)))?;
+                        false
+                    }
+                }
+            }
+            MethodType::Call if args.simulate => {
+                let params = method_params(interface, method, wallet, fixed_params, &mut rng);
+                let call = contract_call(interface, method, &params);
+                let started = Instant::now();
+                let simulated = with_retries(&retry, || simulate_tx(rpc, account, &call)).map_err(anyhow::Error::from);
+                note_latency(args, report, &mut summary, started.elapsed());
+                match simulated {
+                    Ok(simulation) => {
+                        say!(args, "🔮 Simulated: {}{}", result_text(&simulation.result), gas_text(simulation.gas_used));
+                        report.log(method, "simulated", Detail::Simulated { result: &simulation.result, gas_used: simulation.gas_used })?;
+                        true
                     }
                     Err(e) => {
-                        println!("Error: {}", e);
-                        log_to_file(&format!("{}: Error - {}", method.label, e));
+                        say!(args, "Error: {:#}", e);
+                        report.log(method, "error", Detail::Error(&format!("{:#}", e)))?;
+                        false
                     }
                 }
             }
-            "call" => {
-                match call_contract_tx(&client, &wallet.rpc, &sk, &wallet.addr, &interface.contract, &method.name, &params) {
-                    Ok(tx_hash) => {
-                        println!("TX Hash: {}", tx_hash);
-                        log_to_file(&format!("{}: TX Hash {}", method.label, tx_hash));
+            MethodType::Call => {
+                let params = method_params(interface, method, wallet, fixed_params, &mut rng);
+                let call = contract_call(interface, method, &params);
+                let started = Instant::now();
+                // Converted so `{:#}` prints the whole cause chain.
+                let sent = call_contract_tx(rpc, account, &call, &opts, &retry).map_err(anyhow::Error::from);
+                note_latency(args, report, &mut summary, started.elapsed());
+                match sent {
+                    Ok(Submission::Sent(receipt)) => {
+                        remember_nonce(args, &receipt, &account.addr);
+                        checkpoint.mark_done(Checkpoint::key(interface, method, iteration));
+                        say!(args, "TX Hash: {}", receipt.tx_hash);
+                        if let Some(link) = wallet.explorer_link(&receipt.tx_hash) {
+                            say!(args, "🔗 {}", link);
+                        }
+                        report.log(method, "submitted", Detail::TxHash(&receipt.tx_hash))?;
+                        if args.wait {
+                            let timeout = Duration::from_secs(args.wait_timeout);
+                            let interval = Duration::from_secs(args.poll_interval);
+                            match wait_for_confirmation(rpc, &receipt.tx_hash, timeout, interval).map_err(anyhow::Error::from) {
+                                Ok(c) if c.is_confirmed() => {
+                                    match c.fee() {
+                                        Some(fee) => say!(args, "Confirmed (fee {:.6} OCT)", denom.to_oct(fee)),
+                                        None => say!(args, "Confirmed"),
+                                    }
+                                    summary.record_fee(c.fee());
+                                    report.log(method, "confirmed", Detail::Confirmed { tx_hash: &receipt.tx_hash, fee: c.fee(), denom })?;
+                                    true
+                                }
+                                Ok(c) => {
+                                    say!(args, "Not confirmed (status: {})", c.status);
+                                    summary.record_fee(c.fee());
+                                    report.log(method, &c.status, Detail::Confirmed { tx_hash: &receipt.tx_hash, fee: c.fee(), denom })?;
+                                    false
+                                }
+                                Err(e) => {
+                                    say!(args, "Confirmation error: {:#}", e);
+                                    report.log(method, "error", Detail::Error(&format!("confirmation failed: {:#}", e)))?;
+                                    false
+                                }
+                            }
+                        } else {
+                            true
+                        }
+                    }
+                    Ok(Submission::DryRun(body)) => {
+                        print_dry_run(args, &body)?;
+                        say!(args, "Signed, not submitted (dry run)");
+                        report.log(method, "signed", Detail::None)?;
+                        true
                     }
                     Err(e) => {
-                        println!("Error: {}", e);
-                        log_to_file(&format!("{}: Error - {}", method.label, e));
+                        say!(args, "Error: {:#}", e);
+                        report.log(method, "error", Detail::Error(&format!("{:#}", e)))?;
+                        // Every later call would hit the same guard, so stop here.
+                        if let Some(OcsError::NonceBelowMinimum { .. }) = e.downcast_ref::<OcsError>().map(OcsError::root) {
+                            nonce_guard = Some(format!("{:#}", e));
+                        }
+                        false
                     }
                 }
             }
-            _ => println!("Unknown method type"),
+        };
+        summary.record(method, ok);
+        if nonce_guard.is_some() {
+            break;
+        }
+        if !ok && args.fail_fast {
+            let reason = report.last_failure().unwrap_or_else(|| "error".to_string());
+            failed_fast = Some(format!(
+                "--fail-fast: {} failed ({}); stopped after {} of {} methods",
+                method.name, reason, summary.total, runs.len()
+            ));
+            break;
+        }
+        if !was_prefetched && step + 1 < runs.len() && !interrupted() {
+            std::thread::sleep(Duration::from_millis(args.delay)); // Delay antar eksekusi
         }
-        std::thread::sleep(std::time::Duration::from_secs(2)); // Delay antar eksekusi
     }
 
-    println!("\n🎯 Done! U ALREADY COOCKEDD FRR FRR ON GOD!");
-    Ok(())
+    summary.last_nonce = account.last_nonce();
+    say!(args, "\n📊 {}", summary);
+    report.log_summary(&summary)?;
+    let stop = gas_exceeded.or(failed_fast).or(nonce_guard.map(|message| {
+        format!("--min-nonce {} guard: {}; run stopped", args.min_nonce.unwrap_or_default(), message)
+    }));
+    if summary.failed.is_empty() && stop.is_none() && !interrupted() {
+        checkpoint.clear();
+    }
+    Ok(WalletRun { balance, summary, planned: runs.len(), stop })
+}
+
+// =============================
+// MAIN
+// =============================
+fn main() -> Result<()> {
+    let args = parse_args()?;
+    init_logging(args.verbosity)?;
+
+    match &args.command {
+        Command::Run if args.interactive => cmd_repl(&args),
+        Command::Run if args.wallets.is_some() => cmd_fleet(&args),
+        Command::Run => cmd_run(&args),
+        Command::Balance => cmd_balance(&args),
+        Command::Methods => cmd_methods(&args),
+        Command::WhoAmI => cmd_whoami(&args),
+        Command::View { method, params } => cmd_view(&args, method, params),
+        Command::Call { method, params } => cmd_call(&args, method, params),
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_match_usage() {
+        for line in USAGE.lines() {
+            let mut words = line.split_whitespace().skip_while(|w| !w.starts_with("--"));
+            if let Some(flag) = words.next().filter(|_| line.trim_start().starts_with('-')) {
+                let takes_value = words.next().is_some_and(|w| w.starts_with('<'));
+                assert_eq!(option_takes_value(flag), Some(takes_value), "{}", flag);
+            }
+        }
+    }
+
+    fn merged(file: &[&str], cli: &[&str]) -> Vec<String> {
+        let owned = |args: &[&str]| args.iter().map(|a| a.to_string()).collect();
+        merge_config(owned(file), owned(cli))
+    }
+
+    #[test]
+    fn command_line_options_replace_config_values() {
+        let file = ["--delay", "0", "--methods", "a", "--methods", "b", "--header", "X-A: 1", "--quiet"];
+        assert_eq!(merged(&file, &["--methods", "c", "-q"]), ["--delay", "0", "--header", "X-A: 1", "--methods", "c", "-q"]);
+        assert_eq!(merged(&file, &["--header", "X-B: 2"])[..6], ["--delay", "0", "--methods", "a", "--methods", "b"]);
+        assert_eq!(merged(&file, &["run"]).len(), file.len() + 1);
+    }
+
+    #[test]
+    fn command_line_can_turn_off_config_switches() {
+        assert_eq!(merged(&["--dry-run", "--wait"], &["--no-dry-run"]), ["--wait"]);
+        assert_eq!(merged(&[], &["--confirm", "--no-confirm"]), Vec::<String>::new());
+        assert_eq!(merged(&["--verbose"], &["--no-verbose"]), Vec::<String>::new());
+        // Options named --no-... keep their meaning.
+        assert_eq!(merged(&["--no-compression"], &["--no-config"]), ["--no-compression", "--no-config"]);
+        assert_eq!(merged(&["--no-compression"], &["--no-no-compression"]), Vec::<String>::new());
+    }
+
+    #[test]
+    fn exclusive_switches_do_not_conflict_across_sources() {
+        let file = ["--only-views", "--fail-fast"];
+        assert_eq!(merged(&file, &["--only-calls", "--ignore-errors"]), ["--only-calls", "--ignore-errors"]);
+        assert_eq!(merged(&file, &["--only-calls"]), ["--fail-fast", "--only-calls"]);
+    }
+
+    #[test]
+    fn checkpoint_keys_tell_contracts_apart() {
+        let dir = std::env::temp_dir().join(format!("ocs01-checkpoint-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (file, seed) in [("a.json", 1u8), ("b.json", 2)] {
+            let contract = ocs01_auto::derive_address(&SigningKey::from_bytes(&[seed; 32]).verifying_key());
+            let interface = json!({"contract": contract, "methods": [{"name": "claim", "label": "claim", "type": "call", "params": []}]});
+            fs::write(dir.join(file), interface.to_string()).unwrap();
+        }
+        let interface = read_interface_dir(&dir.to_string_lossy(), &mut Sha256::new());
+        fs::remove_dir_all(&dir).unwrap();
+        let interface = interface.unwrap();
+
+        let [first, second] = [0, 1].map(|i| Checkpoint::key(&interface, &interface.methods[i], 1));
+        assert_ne!(first, second);
+        let checkpoint = Checkpoint {
+            path: String::new(),
+            addr: String::new(),
+            interface_sha256: String::new(),
+            completed: vec![first.clone()],
+            enabled: false,
+        };
+        assert!(checkpoint.is_done(&first));
+        assert!(!checkpoint.is_done(&second));
+    }
+
+    #[test]
+    fn config_keys_become_arguments() {
+        let path = std::env::temp_dir().join(format!("ocs01-config-test-{}.toml", std::process::id()));
+        fs::write(&path, "list_methods = true\nquiet = false\ndelay = 0\nmethods = [\"a\", \"b\"]\n").unwrap();
+        let args = config_args(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(args.unwrap(), ["--delay", "0", "--list-methods", "--methods", "a", "--methods", "b"]);
+    }
+}
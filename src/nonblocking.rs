@@ -0,0 +1,266 @@
+//! Async counterparts of the blocking RPC helpers, built on `reqwest::Client` and tokio.
+//!
+//! Signing, [`Account`] nonce tracking and the request formats are shared with the
+//! blocking API. Each wallet's run is an independent future, so several wallets can be
+//! driven at once with e.g. `futures::future::join_all`.
+
+use std::{future::Future, time::{Duration, Instant}};
+
+use reqwest::{Client, Response};
+use serde::Deserialize;
+
+use crate::{
+    batch_body, batch_results, call_body, check_min_nonce, finish_tx, is_not_found, next_retry, note_nonce_resync, redact, view_body, view_result,
+    Account, ApiError, Balance, BalanceResponse, ContractCall, NetworkInfo, OcsError, RateLimiter, Result, RetryPolicy,
+    Simulation, Submission, TxOptions,
+};
+
+// =============================
+// Async RPC
+// =============================
+/// Async HTTP client plus the node endpoints to use, in failover order.
+pub struct AsyncRpc {
+    pub client: Client,
+    pub endpoints: Vec<String>,
+    pub limiter: Option<RateLimiter>,
+}
+
+impl AsyncRpc {
+    pub fn new(client: Client, endpoints: Vec<String>) -> Result<Self> {
+        if endpoints.is_empty() {
+            return Err(OcsError::ConfigLoad("at least one RPC endpoint is required".to_string()));
+        }
+        Ok(AsyncRpc { client, endpoints, limiter: None })
+    }
+
+    /// Async [`crate::Rpc::with_rate_limit`].
+    pub fn with_rate_limit(mut self, rps: f64) -> Result<Self> {
+        self.limiter = Some(RateLimiter::new(rps)?);
+        Ok(self)
+    }
+}
+
+/// Async [`crate::send_request`].
+pub async fn send_request(
+    rpc: &AsyncRpc,
+    method: &str,
+    path: &str,
+    data: Option<&serde_json::Value>
+) -> Result<Response> {
+    send_request_with_timeout(rpc, method, path, data, None).await
+}
+
+/// Async [`crate::send_request_with_timeout`].
+pub async fn send_request_with_timeout(
+    rpc: &AsyncRpc,
+    method: &str,
+    path: &str,
+    data: Option<&serde_json::Value>,
+    timeout: Option<Duration>
+) -> Result<Response> {
+    let mut last_err = None;
+    for base in &rpc.endpoints {
+        let url = format!("{}{}", base, path);
+        let mut req = match method {
+            "GET" => rpc.client.get(&url),
+            "POST" => rpc.client.post(&url).json(&data),
+            _ => return Err(OcsError::ConfigLoad(format!("unsupported HTTP method {}", method))),
+        };
+        if let Some(timeout) = timeout {
+            req = req.timeout(timeout);
+        }
+        if let Some(limiter) = &rpc.limiter {
+            tokio::time::sleep(limiter.reserve()).await;
+        }
+        log::info!("→ {} {}", method, url);
+        if let Some(body) = data {
+            log::debug!("request body: {}", redact(body));
+        }
+
+        let sent = Instant::now();
+        match req.send().await {
+            Ok(resp) if resp.status().is_server_error() => {
+                let status = resp.status().as_u16();
+                let headers = resp.headers().clone();
+                let text = resp.text().await?;
+                log::info!("← {} {} ({} ms)", status, url, sent.elapsed().as_millis());
+                log::debug!("response body: {}", text);
+                last_err = Some(ApiError::new(status, &url, text).with_retry_after(&headers).into());
+            }
+            Ok(resp) => {
+                log::info!("← {} {} ({} ms)", resp.status().as_u16(), url, sent.elapsed().as_millis());
+                return Ok(resp);
+            }
+            // See crate::send_request_with_timeout: a timed-out submission is not failed over.
+            Err(e) if e.is_timeout() && path == "/call-contract" => return Err(e.into()),
+            Err(e) => last_err = Some(e.into()),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| OcsError::ConfigLoad("no RPC endpoints configured".to_string())))
+}
+
+/// Async [`crate::api_call`].
+pub async fn api_call<T: for<'de> Deserialize<'de>>(
+    rpc: &AsyncRpc,
+    method: &str,
+    path: &str,
+    data: Option<serde_json::Value>
+) -> Result<T> {
+    api_call_with_timeout(rpc, method, path, data, None).await
+}
+
+/// Async [`crate::api_call_with_timeout`].
+pub async fn api_call_with_timeout<T: for<'de> Deserialize<'de>>(
+    rpc: &AsyncRpc,
+    method: &str,
+    path: &str,
+    data: Option<serde_json::Value>,
+    timeout: Option<Duration>
+) -> Result<T> {
+    let resp = send_request_with_timeout(rpc, method, path, data.as_ref(), timeout).await?;
+    let (status, url, headers) = (resp.status().as_u16(), resp.url().to_string(), resp.headers().clone());
+    let text = resp.text().await?;
+    log::debug!("response body: {}", text);
+
+    if status >= 400 {
+        return Err(ApiError::new(status, &url, text).with_retry_after(&headers).into());
+    }
+
+    Ok(serde_json::from_str(&text)?)
+}
+
+/// Async [`crate::get_balance`].
+pub async fn get_balance(rpc: &AsyncRpc, addr: &str) -> Result<Balance> {
+    let balance: BalanceResponse = api_call(rpc, "GET", &format!("/balance/{}", addr), None).await?;
+    balance.parse()
+}
+
+/// Async [`crate::get_network`].
+pub async fn get_network(rpc: &AsyncRpc) -> Result<Option<NetworkInfo>> {
+    for path in ["/network", "/info"] {
+        match api_call(rpc, "GET", path, None).await {
+            Ok(raw) => return Ok(Some(NetworkInfo::from_raw(raw))),
+            Err(e) if is_not_found(&e) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(None)
+}
+
+/// Async [`crate::view_call`].
+pub async fn view_call(rpc: &AsyncRpc, contract: &str, method: &str, params: &[String], caller: &str) -> Result<serde_json::Value> {
+    view_call_with_timeout(rpc, contract, method, params, caller, None).await
+}
+
+/// Async [`crate::view_call_string`].
+pub async fn view_call_string(rpc: &AsyncRpc, contract: &str, method: &str, params: &[String], caller: &str) -> Result<String> {
+    let result = view_call(rpc, contract, method, params, caller).await?;
+    Ok(result.as_str().unwrap_or("null").to_string())
+}
+
+/// Async [`crate::view_call_with_timeout`].
+pub async fn view_call_with_timeout(rpc: &AsyncRpc, contract: &str, method: &str, params: &[String], caller: &str, timeout: Option<Duration>) -> Result<serde_json::Value> {
+    let body = view_body(contract, method, params, caller);
+    let res: serde_json::Value = api_call_with_timeout(rpc, "POST", "/contract/call-view", Some(body), timeout).await?;
+    view_result(res)
+}
+
+/// Async [`crate::batch_view_call`].
+pub async fn batch_view_call(rpc: &AsyncRpc, contract: &str, calls: &[(String, Vec<String>)], caller: &str) -> Result<Vec<Result<serde_json::Value>>> {
+    let body = batch_body(contract, calls, caller);
+    match api_call(rpc, "POST", "/contract/call-view-batch", Some(body)).await {
+        Ok(res) => batch_results(res, calls.len()),
+        Err(e) if is_not_found(&e) => {
+            log::info!("no batch view endpoint, falling back to single view calls");
+            let mut results = Vec::with_capacity(calls.len());
+            for (method, params) in calls {
+                results.push(view_call(rpc, contract, method, params, caller).await);
+            }
+            Ok(results)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Async [`crate::with_retries`].
+pub async fn with_retries<T, F, Fut>(retry: &RetryPolicy, mut op: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) => tokio::time::sleep(next_retry(retry, attempt, e)?).await,
+        }
+        attempt += 1;
+    }
+}
+
+// =============================
+// Async TX
+// =============================
+/// Async [`crate::call_contract_tx`].
+///
+/// The retry loop is spelled out rather than going through [`with_retries`], since each
+/// attempt borrows `account` mutably.
+pub async fn call_contract_tx(rpc: &AsyncRpc, account: &mut Account, call: &ContractCall<'_>, opts: &TxOptions, retry: &RetryPolicy) -> Result<Submission> {
+    let (mut attempt, mut resyncs) = (1, 0);
+    loop {
+        let e = match try_send_tx(rpc, account, call, opts).await {
+            Ok(submission) => return Ok(submission),
+            Err(e) => e,
+        };
+        match next_retry(retry, attempt, e) {
+            Ok(delay) => {
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) if e.is_nonce_conflict() && resyncs < retry.nonce_resyncs => {
+                resyncs += 1;
+                note_nonce_resync(account, resyncs, retry);
+                attempt = 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Async [`crate::try_send_tx`].
+pub async fn try_send_tx(rpc: &AsyncRpc, account: &mut Account, call: &ContractCall<'_>, opts: &TxOptions) -> Result<Submission> {
+    let nonce = next_nonce(rpc, account).await?;
+    check_min_nonce(nonce, opts)?;
+    let (timestamp, blob, body) = call_body(account, nonce, call)?;
+
+    if opts.dry_run {
+        account.commit_nonce(nonce);
+        return Ok(Submission::DryRun(body));
+    }
+
+    if let Some(audit) = &opts.audit {
+        audit.record_signed(&blob, &body)?;
+    }
+    let res = api_call_with_timeout(rpc, "POST", "/call-contract", Some(body.clone()), call.timeout).await;
+    if let Some(audit) = &opts.audit {
+        audit.record_response(&body, &res);
+    }
+    finish_tx(account, nonce, timestamp, res).map(Submission::Sent)
+}
+
+/// Async [`crate::simulate_tx`].
+pub async fn simulate_tx(rpc: &AsyncRpc, account: &mut Account, call: &ContractCall<'_>) -> Result<Simulation> {
+    let nonce = next_nonce(rpc, account).await?;
+    let (_, _, body) = call_body(account, nonce, call)?;
+    let res = api_call_with_timeout(rpc, "POST", "/simulate-contract", Some(body), call.timeout).await?;
+    Simulation::from_raw(res)
+}
+
+/// Async [`Account::next_nonce`].
+async fn next_nonce(rpc: &AsyncRpc, account: &mut Account) -> Result<u64> {
+    let last = match account.last_nonce {
+        Some(n) => n,
+        None => account.seed_nonce(get_balance(rpc, &account.addr).await?.nonce),
+    };
+    Ok(last + 1)
+}
@@ -0,0 +1,325 @@
+//! RPC helpers against a minimal in-process HTTP stub.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ed25519_dalek::SigningKey;
+use ocs01_auto::{
+    call_contract_tx, derive_address, get_balance, try_send_tx, view_call, Account, ContractCall, OcsError, RetryPolicy, Rpc,
+    Submission, TxOptions,
+};
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+
+type Handler = dyn Fn(&str, &str, &Value) -> (u16, Value) + Send + Sync;
+
+/// A node stub on a random local port. Every request is answered by `handler` from
+/// `(method, path, body)` and recorded as `"METHOD path"`; `headers` (each ending in
+/// `\r\n`) are added to every response.
+struct Stub {
+    url: String,
+    hits: Arc<Mutex<Vec<String>>>,
+}
+
+impl Stub {
+    fn start(handler: impl Fn(&str, &str, &Value) -> (u16, Value) + Send + Sync + 'static) -> Self {
+        Self::start_with_headers("", handler)
+    }
+
+    fn start_with_headers(headers: &'static str, handler: impl Fn(&str, &str, &Value) -> (u16, Value) + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+        let recorded = Arc::clone(&hits);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let mut parts = line.split_whitespace();
+                let (method, path) = (parts.next().unwrap_or("").to_string(), parts.next().unwrap_or("").to_string());
+
+                let mut len = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        len = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; len];
+                reader.read_exact(&mut body).unwrap();
+                let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+                recorded.lock().unwrap().push(format!("{} {}", method, path));
+                let (status, reply) = handler(&method, &path, &body);
+                let reply = reply.to_string();
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {} Stub\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+                    status,
+                    reply.len(),
+                    headers,
+                    reply
+                );
+            }
+        });
+        Stub { url, hits }
+    }
+
+    fn hits(&self) -> Vec<String> {
+        self.hits.lock().unwrap().clone()
+    }
+}
+
+/// An endpoint nothing listens on.
+fn dead_endpoint() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+fn rpc(endpoints: &[&str]) -> Rpc {
+    Rpc::new(Client::new(), endpoints.iter().map(|e| e.to_string()).collect()).unwrap()
+}
+
+fn account() -> Account {
+    let sk = SigningKey::from_bytes(&[7u8; 32]);
+    let addr = derive_address(&sk.verifying_key());
+    Account::new(sk, addr)
+}
+
+fn call(params: &[String]) -> ContractCall<'_> {
+    ContractCall { contract: "octContract", method: "claim", params, ou: 1, amount: 0, timeout: None }
+}
+
+fn opts() -> TxOptions {
+    TxOptions { dry_run: false, min_nonce: None, audit: None }
+}
+
+/// Answers balance requests with nonce 4 and submissions with `submit`.
+fn node(submit: impl Fn(&Value) -> (u16, Value) + Send + Sync + 'static) -> Stub {
+    node_with_headers("", submit)
+}
+
+fn node_with_headers(headers: &'static str, submit: impl Fn(&Value) -> (u16, Value) + Send + Sync + 'static) -> Stub {
+    Stub::start_with_headers(headers, move |method, path, body| match (method, path) {
+        ("GET", p) if p.starts_with("/balance/") => (200, json!({"balance_raw": "1500000", "nonce": 4})),
+        ("POST", "/call-contract") => submit(body),
+        _ => (404, json!({"error": "not found"})),
+    })
+}
+
+/// Submissions answered with `first` once, then accepted.
+fn flaky_node(headers: &'static str, first: (u16, Value)) -> Stub {
+    let posts = AtomicUsize::new(0);
+    node_with_headers(headers, move |body| match posts.fetch_add(1, Ordering::SeqCst) {
+        0 => first.clone(),
+        _ => (200, json!({"tx_hash": format!("tx{}", body["nonce"])})),
+    })
+}
+
+/// Retries with no backoff beyond the built-in jitter.
+fn policy(max_attempts: u32, nonce_resyncs: u32) -> RetryPolicy {
+    RetryPolicy { max_attempts, base_delay: Duration::from_millis(1), max_delay: Duration::from_secs(5), nonce_resyncs }
+}
+
+fn posts(stub: &Stub) -> usize {
+    stub.hits().iter().filter(|hit| hit.starts_with("POST")).count()
+}
+
+fn api_status(e: &OcsError) -> Option<u16> {
+    match e.root() {
+        OcsError::Api(api) => Some(api.status),
+        _ => None,
+    }
+}
+
+#[test]
+fn get_balance_parses_balance_and_nonce() {
+    let stub = node(|_| (500, Value::Null));
+    let balance = get_balance(&rpc(&[&stub.url]), "octAddr").unwrap();
+    assert_eq!((balance.micro, balance.nonce), (1_500_000, 4));
+    assert_eq!(stub.hits(), ["GET /balance/octAddr"]);
+}
+
+#[test]
+fn view_call_returns_result_and_sends_caller() {
+    let stub = Stub::start(|_, _, body| {
+        assert_eq!(body["method"], "hello");
+        assert_eq!(body["caller"], "octCaller");
+        (200, json!({"status": "success", "result": {"n": 3}}))
+    });
+    let result = view_call(&rpc(&[&stub.url]), "octContract", "hello", &[], "octCaller").unwrap();
+    assert_eq!(result, json!({"n": 3}));
+    assert_eq!(stub.hits(), ["POST /contract/call-view"]);
+}
+
+#[test]
+fn view_call_reports_a_failed_status() {
+    let stub = Stub::start(|_, _, _| (200, json!({"status": "error", "error": "reverted"})));
+    let err = view_call(&rpc(&[&stub.url]), "octContract", "hello", &[], "octCaller").unwrap_err();
+    assert!(matches!(err, OcsError::View(_)), "{:?}", err);
+}
+
+#[test]
+fn client_errors_do_not_fail_over() {
+    let first = Stub::start(|_, _, _| (400, json!({"error": "bad params"})));
+    let second = Stub::start(|_, _, _| (200, json!({"status": "success", "result": 1})));
+    let err = view_call(&rpc(&[&first.url, &second.url]), "c", "m", &[], "a").unwrap_err();
+    assert_eq!(api_status(&err), Some(400));
+    assert!(second.hits().is_empty());
+}
+
+#[test]
+fn server_errors_fail_over_to_the_next_endpoint() {
+    let first = Stub::start(|_, _, _| (503, json!({"error": "overloaded"})));
+    let second = Stub::start(|_, _, _| (200, json!({"status": "success", "result": "ok"})));
+    let dead = dead_endpoint();
+    let result = view_call(&rpc(&[&dead, &first.url, &second.url]), "c", "m", &[], "a").unwrap();
+    assert_eq!(result, "ok");
+    assert_eq!(first.hits().len(), 1);
+    assert_eq!(second.hits().len(), 1);
+}
+
+#[test]
+fn the_last_server_error_is_returned_when_every_endpoint_fails() {
+    let first = Stub::start(|_, _, _| (502, Value::Null));
+    let second = Stub::start(|_, _, _| (503, json!({"error": "down"})));
+    let err = get_balance(&rpc(&[&first.url, &second.url]), "octAddr").unwrap_err();
+    assert_eq!(api_status(&err), Some(503));
+}
+
+#[test]
+fn try_send_tx_submits_the_next_nonce() {
+    let stub = node(|body| {
+        assert_eq!(body["nonce"], 5);
+        assert_eq!(body["params"], json!(["7"]));
+        (200, json!({"tx_hash": "abc"}))
+    });
+    let mut account = account();
+    let params = vec!["7".to_string()];
+    let Submission::Sent(receipt) = try_send_tx(&rpc(&[&stub.url]), &mut account, &call(&params), &opts()).unwrap() else {
+        panic!("expected a submitted transaction");
+    };
+    assert_eq!((receipt.tx_hash.as_str(), receipt.nonce), ("abc", 5));
+    assert_eq!(account.last_nonce(), Some(5));
+}
+
+#[test]
+fn try_send_tx_counts_nonces_locally_after_the_first_read() {
+    let stub = node(|body| (200, json!({"tx_hash": format!("tx{}", body["nonce"])})));
+    let (rpc, mut account) = (rpc(&[&stub.url]), account());
+    for expected in [5, 6] {
+        let Submission::Sent(receipt) = try_send_tx(&rpc, &mut account, &call(&[]), &opts()).unwrap() else {
+            panic!("expected a submitted transaction");
+        };
+        assert_eq!(receipt.nonce, expected);
+    }
+    assert_eq!(stub.hits().iter().filter(|hit| hit.starts_with("GET")).count(), 1);
+}
+
+#[test]
+fn try_send_tx_rejects_a_response_without_tx_hash() {
+    let stub = node(|_| (200, json!({"status": "queued"})));
+    let mut account = account();
+    let err = try_send_tx(&rpc(&[&stub.url]), &mut account, &call(&[]), &opts()).err().expect("submission should fail");
+    assert!(matches!(&err, OcsError::Rejected(detail) if detail.contains("queued")), "{:?}", err);
+    // The nonce was not used, so the next attempt signs it again.
+    assert_eq!(account.last_nonce(), Some(4));
+}
+
+#[test]
+fn try_send_tx_resyncs_after_a_nonce_error() {
+    let stub = node(|_| (400, json!({"error": "invalid nonce: expected 9"})));
+    let mut account = account();
+    let err = try_send_tx(&rpc(&[&stub.url]), &mut account, &call(&[]), &opts()).err().expect("submission should fail");
+    assert_eq!(api_status(&err), Some(400));
+    assert!(err.is_nonce_conflict());
+    assert_eq!(account.last_nonce(), None);
+}
+
+#[test]
+fn try_send_tx_returns_the_signed_body_on_a_dry_run() {
+    let stub = node(|_| panic!("a dry run must not submit"));
+    let mut account = account();
+    let opts = TxOptions { dry_run: true, ..opts() };
+    let Submission::DryRun(body) = try_send_tx(&rpc(&[&stub.url]), &mut account, &call(&[]), &opts).unwrap() else {
+        panic!("expected a dry run");
+    };
+    assert_eq!((body["nonce"].as_u64(), body["method"].as_str()), (Some(5), Some("claim")));
+    assert_eq!(stub.hits(), [format!("GET /balance/{}", account.addr)]);
+}
+
+#[test]
+fn call_contract_tx_does_not_retry_a_timed_out_submission() {
+    let stub = node(|body| {
+        thread::sleep(Duration::from_millis(1500));
+        (200, json!({"tx_hash": format!("tx{}", body["nonce"])}))
+    });
+    let mut account = account();
+    let call = ContractCall { timeout: Some(Duration::from_millis(500)), ..call(&[]) };
+    let err = call_contract_tx(&rpc(&[&stub.url]), &mut account, &call, &opts(), &RetryPolicy::default())
+        .err()
+        .expect("submission should time out");
+    assert!(matches!(err.root(), OcsError::SubmitTimedOut { nonce: 5, .. }), "{:?}", err);
+    assert_eq!(posts(&stub), 1);
+    // Whether nonce 5 was used is unknown, so the next transaction asks the node.
+    assert_eq!(account.last_nonce(), None);
+}
+
+#[test]
+fn call_contract_tx_retries_a_server_error() {
+    let stub = flaky_node("", (503, json!({"error": "overloaded"})));
+    let mut account = account();
+    let Submission::Sent(receipt) = call_contract_tx(&rpc(&[&stub.url]), &mut account, &call(&[]), &opts(), &policy(3, 0)).unwrap() else {
+        panic!("expected a submitted transaction");
+    };
+    assert_eq!((receipt.tx_hash.as_str(), receipt.nonce), ("tx5", 5));
+    assert_eq!(posts(&stub), 2);
+}
+
+#[test]
+fn call_contract_tx_waits_for_retry_after() {
+    let stub = flaky_node("Retry-After: 1\r\n", (429, json!({"error": "slow down"})));
+    let mut account = account();
+    let started = Instant::now();
+    let submission = call_contract_tx(&rpc(&[&stub.url]), &mut account, &call(&[]), &opts(), &policy(3, 0)).unwrap();
+    assert!(matches!(submission, Submission::Sent(_)));
+    // The backoff alone would be at most 1ms plus 500ms of jitter.
+    assert!(started.elapsed() >= Duration::from_secs(1), "{:?}", started.elapsed());
+    assert_eq!(posts(&stub), 2);
+}
+
+#[test]
+fn call_contract_tx_does_not_retry_a_client_error() {
+    let stub = flaky_node("", (400, json!({"error": "bad params"})));
+    let mut account = account();
+    let err = call_contract_tx(&rpc(&[&stub.url]), &mut account, &call(&[]), &opts(), &policy(3, 0))
+        .err()
+        .expect("submission should fail");
+    assert!(matches!(err, OcsError::Permanent(_)), "{:?}", err);
+    assert_eq!(api_status(&err), Some(400));
+    assert_eq!(posts(&stub), 1);
+}
+
+#[test]
+fn call_contract_tx_resyncs_the_nonce_and_starts_over() {
+    let stub = flaky_node("", (400, json!({"error": "invalid nonce: expected 5"})));
+    let mut account = account();
+    // One attempt per sequence, so only the resync can get the call through.
+    let submission = call_contract_tx(&rpc(&[&stub.url]), &mut account, &call(&[]), &opts(), &policy(1, 1)).unwrap();
+    assert!(matches!(submission, Submission::Sent(_)));
+    assert_eq!(posts(&stub), 2);
+    assert_eq!(stub.hits().iter().filter(|hit| hit.starts_with("GET")).count(), 2);
+}